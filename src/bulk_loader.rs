@@ -0,0 +1,500 @@
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    mem,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    db::{DBCommon, DBInner},
+    ffi, AsColumnFamilyRef, Error, IngestExternalFileOptions, Options, SstFileWriter, ThreadMode,
+};
+
+const DEFAULT_MEMORY_BUDGET: usize = 64 << 20;
+const DEFAULT_TARGET_FILE_SIZE: u64 = 64 << 20;
+// The most runs read at once, which keeps the number of open files bounded.
+const MAX_MERGE_WIDTH: usize = 64;
+
+/// Loads key-value pairs given in any order into one or more column families
+/// through external SST files.
+///
+/// Pairs are buffered in memory. Whenever the buffered data exceeds the memory
+/// budget, it is sorted and spilled as a run into the working directory.
+/// [`finish`] merges the runs of each column family into SST files of about
+/// the target file size and ingests them. Runs are merged at most 64 at a
+/// time, in several passes if there are more. When a key is added more than
+/// once, the value added last wins.
+///
+/// Keys are ordered bytewise, so the column families must use the default
+/// comparator. The SST files of each column family are written with the
+/// [`Options`] given for it, which should match those it was opened with:
+/// those passed to [`new`] for the default column family, and those passed to
+/// [`set_cf_options`] for any other.
+///
+/// ```
+/// use rocksdb::{BulkLoader, IngestExternalFileOptions, Options, DB};
+///
+/// let tempdir = tempfile::Builder::new()
+///     .prefix("_path_for_rocksdb_storage_bulk_loader")
+///     .tempdir()
+///     .expect("Failed to create temporary path for the _path_for_rocksdb_storage_bulk_loader.");
+/// let path = tempdir.path();
+/// {
+///     let mut opts = Options::default();
+///     opts.create_if_missing(true);
+///     let db = DB::open(&opts, path.join("db")).unwrap();
+///
+///     let mut loader = BulkLoader::new(&db, &opts, path.join("bulk")).unwrap();
+///     loader.put(b"k2", b"v2").unwrap();
+///     loader.put(b"k1", b"v1").unwrap();
+///     loader.put(b"k2", b"v3").unwrap();
+///     loader.finish(&IngestExternalFileOptions::default()).unwrap();
+///
+///     assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+///     assert_eq!(db.get(b"k2").unwrap().unwrap(), b"v3");
+/// }
+/// let _ = DB::destroy(&Options::default(), path.join("db"));
+/// ```
+///
+/// [`finish`]: BulkLoader::finish
+/// [`new`]: BulkLoader::new
+/// [`set_cf_options`]: BulkLoader::set_cf_options
+pub struct BulkLoader<'a, T: ThreadMode, I: DBInner> {
+    db: &'a DBCommon<T, I>,
+    opts: &'a Options,
+    dir: PathBuf,
+    memory_budget: usize,
+    target_file_size: u64,
+    buffered_bytes: usize,
+    next_seq: u64,
+    next_file: usize,
+    cfs: Vec<CfLoad<'a>>,
+}
+
+struct CfLoad<'a> {
+    // Loads are told apart by column family ID, as several handles may refer
+    // to the same column family. The default column family always has ID 0.
+    id: u32,
+    // `None` if the first pair was added through `BulkLoader::put`. The handle
+    // is borrowed by `BulkLoader::put_cf` for the lifetime of the loader.
+    cf: Option<*mut ffi::rocksdb_column_family_handle_t>,
+    // The options the SST files of the column family are written with.
+    opts: &'a Options,
+    entries: Vec<Entry>,
+    runs: Vec<PathBuf>,
+}
+
+impl<'a> CfLoad<'a> {
+    fn new(
+        id: u32,
+        cf: Option<*mut ffi::rocksdb_column_family_handle_t>,
+        opts: &'a Options,
+    ) -> Self {
+        Self {
+            id,
+            cf,
+            opts,
+            entries: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+}
+
+struct Entry {
+    key: Vec<u8>,
+    value: Vec<u8>,
+    seq: u64,
+}
+
+struct CfRef(*mut ffi::rocksdb_column_family_handle_t);
+
+impl AsColumnFamilyRef for CfRef {
+    fn inner(&self) -> *mut ffi::rocksdb_column_family_handle_t {
+        self.0
+    }
+}
+
+impl<'a, T: ThreadMode, I: DBInner> BulkLoader<'a, T, I> {
+    /// Creates a loader for `db` which writes the SST files of the default
+    /// column family with `opts` and keeps its temporary files in a new
+    /// directory inside `dir`, created if missing. Several loaders may share
+    /// `dir`.
+    pub fn new<P: AsRef<Path>>(
+        db: &'a DBCommon<T, I>,
+        opts: &'a Options,
+        dir: P,
+    ) -> Result<Self, Error> {
+        let dir = create_work_dir(dir.as_ref()).map_err(|e| {
+            Error::new(format!(
+                "IO error: Failed to create a directory in {}: {e}",
+                dir.as_ref().display()
+            ))
+        })?;
+        Ok(Self {
+            db,
+            opts,
+            dir,
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+            target_file_size: DEFAULT_TARGET_FILE_SIZE,
+            buffered_bytes: 0,
+            next_seq: 0,
+            next_file: 0,
+            cfs: Vec::new(),
+        })
+    }
+
+    /// Sets how many bytes of pairs are buffered in memory before they are
+    /// spilled to the working directory.
+    ///
+    /// Default: 64MB
+    pub fn set_memory_budget(&mut self, bytes: usize) {
+        self.memory_budget = bytes;
+    }
+
+    /// Sets the size at which an SST file is finished and a new one started.
+    ///
+    /// Default: 64MB
+    pub fn set_target_file_size(&mut self, bytes: u64) {
+        self.target_file_size = bytes;
+    }
+
+    /// Sets the options the SST files of the column family `cf` are written
+    /// with. This must be called before pairs are added with
+    /// [`put_cf`](Self::put_cf) for any column family but the default one.
+    pub fn set_cf_options(&mut self, cf: &'a impl AsColumnFamilyRef, opts: &'a Options) {
+        let cf = cf.inner();
+        let id = unsafe { ffi::rocksdb_column_family_handle_get_id(cf) };
+        match self.cfs.iter_mut().find(|load| load.id == id) {
+            Some(load) => load.opts = opts,
+            None => self.cfs.push(CfLoad::new(id, Some(cf), opts)),
+        }
+    }
+
+    /// Adds a pair for the default column family.
+    pub fn put<K, V>(&mut self, key: K, value: V) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        self.put_raw(None, key.as_ref(), value.as_ref())
+    }
+
+    /// Adds a pair for the column family `cf`.
+    ///
+    /// Fails if `cf` is not the default column family and its options were
+    /// not set with [`set_cf_options`](Self::set_cf_options).
+    pub fn put_cf<K, V>(
+        &mut self,
+        cf: &'a impl AsColumnFamilyRef,
+        key: K,
+        value: V,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        self.put_raw(Some(cf.inner()), key.as_ref(), value.as_ref())
+    }
+
+    fn put_raw(
+        &mut self,
+        cf: Option<*mut ffi::rocksdb_column_family_handle_t>,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), Error> {
+        let id = cf.map_or(0, |cf| unsafe {
+            ffi::rocksdb_column_family_handle_get_id(cf)
+        });
+        let index = if let Some(index) = self.cfs.iter().position(|load| load.id == id) {
+            index
+        } else if id == 0 {
+            self.cfs.push(CfLoad::new(id, cf, self.opts));
+            self.cfs.len() - 1
+        } else {
+            return Err(Error::new(format!(
+                "No options set for column family {id}, see BulkLoader::set_cf_options"
+            )));
+        };
+        let load = &mut self.cfs[index];
+        load.entries.push(Entry {
+            key: key.to_vec(),
+            value: value.to_vec(),
+            seq: self.next_seq,
+        });
+        self.next_seq += 1;
+        self.buffered_bytes += key.len() + value.len() + mem::size_of::<Entry>();
+        if self.buffered_bytes > self.memory_budget {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered pairs of every column family as sorted runs.
+    fn spill(&mut self) -> Result<(), Error> {
+        for index in 0..self.cfs.len() {
+            if self.cfs[index].entries.is_empty() {
+                continue;
+            }
+            let mut entries = mem::take(&mut self.cfs[index].entries);
+            // Newest first among equal keys, so that dedup keeps the last write.
+            entries.sort_unstable_by(|a, b| a.key.cmp(&b.key).then(b.seq.cmp(&a.seq)));
+            entries.dedup_by(|a, b| a.key == b.key);
+
+            let path = self.next_path(index, "run");
+            let mut writer = RunWriter::create(&path)?;
+            for entry in &entries {
+                writer.write(&entry.key, &entry.value)?;
+            }
+            writer.finish()?;
+            self.cfs[index].runs.push(path);
+        }
+        self.buffered_bytes = 0;
+        Ok(())
+    }
+
+    /// Merges the pairs of each column family into SST files and then ingests
+    /// them with `opts`. The temporary files are removed afterwards, whether
+    /// or not this succeeds.
+    ///
+    /// The SST files of every column family are written before any is
+    /// ingested, so a failure while writing them leaves the database as it
+    /// was. The load is not atomic across column families though: each one is
+    /// ingested with its own call, as the C API has no way to ingest into
+    /// several at once. If ingesting into one column family fails, those
+    /// ingested before it keep their pairs.
+    pub fn finish(mut self, opts: &IngestExternalFileOptions) -> Result<(), Error> {
+        self.spill()?;
+        let mut ssts = Vec::with_capacity(self.cfs.len());
+        for index in 0..self.cfs.len() {
+            ssts.push(self.write_ssts(index)?);
+        }
+        for (load, ssts) in self.cfs.iter().zip(ssts) {
+            match load.cf {
+                Some(cf) => self
+                    .db
+                    .ingest_external_file_cf_opts(&CfRef(cf), opts, ssts)?,
+                None => self.db.ingest_external_file_opts(opts, ssts)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Merges the runs of the column family at `index` into SST files and
+    /// returns their paths.
+    fn write_ssts(&mut self, index: usize) -> Result<Vec<PathBuf>, Error> {
+        let mut runs = mem::take(&mut self.cfs[index].runs);
+        // Merging consecutive runs keeps them ordered from oldest to newest.
+        while runs.len() > MAX_MERGE_WIDTH {
+            let mut merged = Vec::with_capacity(runs.len().div_ceil(MAX_MERGE_WIDTH));
+            for chunk in runs.chunks(MAX_MERGE_WIDTH) {
+                let path = self.next_path(index, "run");
+                let mut writer = RunWriter::create(&path)?;
+                merge_runs(chunk, |key, value| writer.write(key, value))?;
+                writer.finish()?;
+                for run in chunk {
+                    let _ = fs::remove_file(run);
+                }
+                merged.push(path);
+            }
+            runs = merged;
+        }
+
+        let mut ssts = Vec::new();
+        let mut writer: Option<SstFileWriter> = None;
+        merge_runs(&runs, |key, value| {
+            if writer.is_none() {
+                let path = self.next_path(index, "sst");
+                let mut w = SstFileWriter::create(self.cfs[index].opts);
+                w.open(&path)?;
+                writer = Some(w);
+                ssts.push(path);
+            }
+            let w = writer.as_mut().unwrap();
+            w.put(key, value)?;
+            if w.file_size() >= self.target_file_size {
+                w.finish()?;
+                writer = None;
+            }
+            Ok(())
+        })?;
+        if let Some(mut w) = writer {
+            w.finish()?;
+        }
+        Ok(ssts)
+    }
+
+    fn next_path(&mut self, index: usize, extension: &str) -> PathBuf {
+        let path = self
+            .dir
+            .join(format!("{index}-{}.{extension}", self.next_file));
+        self.next_file += 1;
+        path
+    }
+}
+
+impl<T: ThreadMode, I: DBInner> Drop for BulkLoader<'_, T, I> {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// The next pair of a run, ordered by smallest key and then by newest run, so
+/// that it can be popped from a max-heap.
+struct HeapItem {
+    key: Vec<u8>,
+    value: Vec<u8>,
+    run: usize,
+}
+
+impl Ord for HeapItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.cmp(&self.key).then(self.run.cmp(&other.run))
+    }
+}
+
+impl PartialOrd for HeapItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HeapItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeapItem {}
+
+/// Creates a directory inside `dir` that no other loader uses.
+fn create_work_dir(dir: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let mut n = 0;
+    loop {
+        let path = dir.join(format!("bulk-loader-{}-{n}", std::process::id()));
+        match fs::create_dir(&path) {
+            Ok(()) => return Ok(path),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => n += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Merges `runs`, ordered from oldest to newest, and passes each key with its
+/// newest value to `f` in key order.
+fn merge_runs(
+    runs: &[PathBuf],
+    mut f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut readers = Vec::with_capacity(runs.len());
+    let mut heap = BinaryHeap::with_capacity(runs.len());
+    for (run, path) in runs.iter().enumerate() {
+        let mut reader = RunReader::open(path)?;
+        if let Some((key, value)) = reader.read()? {
+            heap.push(HeapItem { key, value, run });
+        }
+        readers.push(reader);
+    }
+
+    let mut last_key: Option<Vec<u8>> = None;
+    while let Some(item) = heap.pop() {
+        if let Some((key, value)) = readers[item.run].read()? {
+            heap.push(HeapItem {
+                key,
+                value,
+                run: item.run,
+            });
+        }
+        // The newest run holding a key is popped first, the others are stale.
+        if last_key.as_ref() == Some(&item.key) {
+            continue;
+        }
+        f(&item.key, &item.value)?;
+        last_key = Some(item.key);
+    }
+    Ok(())
+}
+
+// Runs hold length-prefixed keys and values, with little-endian u64 lengths.
+
+type Pair = (Vec<u8>, Vec<u8>);
+
+struct RunWriter<'p> {
+    path: &'p Path,
+    writer: BufWriter<File>,
+}
+
+impl<'p> RunWriter<'p> {
+    fn create(path: &'p Path) -> Result<Self, Error> {
+        let writer = File::create_new(path)
+            .map(BufWriter::new)
+            .map_err(|e| write_error(path, e))?;
+        Ok(Self { path, writer })
+    }
+
+    fn write(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        for bytes in [key, value] {
+            self.writer
+                .write_all(&(bytes.len() as u64).to_le_bytes())
+                .and_then(|()| self.writer.write_all(bytes))
+                .map_err(|e| write_error(self.path, e))?;
+        }
+        Ok(())
+    }
+
+    // Runs are removed once the load is done, so they are not synced.
+    fn finish(mut self) -> Result<(), Error> {
+        self.writer.flush().map_err(|e| write_error(self.path, e))
+    }
+}
+
+struct RunReader<'p> {
+    path: &'p Path,
+    reader: BufReader<File>,
+}
+
+impl<'p> RunReader<'p> {
+    fn open(path: &'p Path) -> Result<Self, Error> {
+        let reader = File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| read_error(path, e))?;
+        Ok(Self { path, reader })
+    }
+
+    fn read(&mut self) -> Result<Option<Pair>, Error> {
+        self.read_pair().map_err(|e| read_error(self.path, e))
+    }
+
+    fn read_pair(&mut self) -> io::Result<Option<Pair>> {
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        let key = self.read_bytes()?;
+        let value = self.read_bytes()?;
+        Ok(Some((key, value)))
+    }
+
+    fn read_bytes(&mut self) -> io::Result<Vec<u8>> {
+        let mut len = [0; 8];
+        self.reader.read_exact(&mut len)?;
+        let mut bytes = vec![0; u64::from_le_bytes(len) as usize];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+fn write_error(path: &Path, e: io::Error) -> Error {
+    Error::new(format!(
+        "IO error: Failed to write run {}: {e}",
+        path.display()
+    ))
+}
+
+fn read_error(path: &Path, e: io::Error) -> Error {
+    Error::new(format!(
+        "IO error: Failed to read run {}: {e}",
+        path.display()
+    ))
+}
@@ -19,9 +19,11 @@ use crate::{
     column_family::UnboundColumnFamily,
     db_options::OptionsMustOutliveDB,
     ffi,
-    ffi_util::{from_cstr, opt_bytes_to_ptr, raw_data, to_cpath, CStrLike},
-    ColumnFamily, ColumnFamilyDescriptor, CompactOptions, DBIteratorWithThreadMode,
-    DBPinnableSlice, DBRawIteratorWithThreadMode, DBWALIterator, Direction, Error, FlushOptions,
+    ffi_util::{from_cstr, from_cstr_and_free, opt_bytes_to_ptr, raw_data, to_cpath, CStrLike},
+    merge_operator::{self, CapturedMerge, UnmergedValue},
+    AttributeGroupIteratorWithThreadMode, CoalescingIteratorWithThreadMode, ColumnFamily,
+    ColumnFamilyDescriptor, CompactOptions, DBIteratorWithThreadMode, DBPinnableSlice,
+    DBRawIteratorWithThreadMode, DBWALIterator, Direction, Error, ErrorKind, FlushOptions,
     IngestExternalFileOptions, IteratorMode, Options, ReadOptions, SnapshotWithThreadMode,
     WaitForCompactOptions, WriteBatch, WriteOptions, DEFAULT_COLUMN_FAMILY_NAME,
 };
@@ -1148,6 +1150,79 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         self.get_pinned_cf_opt(cf, key, &ReadOptions::default())
     }
 
+    /// Returns the base value and the merge operands of a key without merging
+    /// them, or `None` if the key does not exist. Similar to
+    /// get_merge_operands_opt but leverages default options.
+    pub fn get_merge_operands<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        max_operands: usize,
+    ) -> Result<Option<UnmergedValue>, Error> {
+        self.get_merge_operands_opt(key, max_operands, &ReadOptions::default())
+    }
+
+    /// Returns the base value and the merge operands of a key without merging
+    /// them, or `None` if the key does not exist.
+    ///
+    /// The operands are listed in the order they are applied. If the key has
+    /// more than `max_operands` operands, an [`ErrorKind::Incomplete`] error
+    /// is returned and no operand is copied, although RocksDB still reads them
+    /// all. A snapshot set on `readopts` is honored.
+    ///
+    /// This is an emulation, not a binding of RocksDB's `GetMergeOperands`,
+    /// which the C API does not expose, and has these limits:
+    ///
+    /// - While the key is read, the merge operator installed through
+    ///   [`Options::set_merge_operator`] or
+    ///   [`Options::set_merge_operator_associative`] records its inputs
+    ///   instead of calling the merge function. Other merge operators are not
+    ///   supported.
+    /// - It depends on a point read running the full merge synchronously on
+    ///   the calling thread, which RocksDB does internally but does not
+    ///   document.
+    /// - To tell captured reads apart, every full merge of those merge
+    ///   operators checks a thread-local flag, in every database and also in
+    ///   flushes and compactions.
+    pub fn get_merge_operands_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        max_operands: usize,
+        readopts: &ReadOptions,
+    ) -> Result<Option<UnmergedValue>, Error> {
+        let (value, captured) = merge_operator::capture_merge_operands(max_operands, || {
+            self.get_pinned_opt(key, readopts)
+        });
+        unmerged_value(value?, captured)
+    }
+
+    /// Returns the base value and the merge operands of a key without merging
+    /// them, or `None` if the key does not exist. Similar to
+    /// get_merge_operands_cf_opt but leverages default options.
+    pub fn get_merge_operands_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        max_operands: usize,
+    ) -> Result<Option<UnmergedValue>, Error> {
+        self.get_merge_operands_cf_opt(cf, key, max_operands, &ReadOptions::default())
+    }
+
+    /// Returns the base value and the merge operands of a key without merging
+    /// them, or `None` if the key does not exist. Similar to
+    /// get_merge_operands_opt but allows specifying ColumnFamily.
+    pub fn get_merge_operands_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        max_operands: usize,
+        readopts: &ReadOptions,
+    ) -> Result<Option<UnmergedValue>, Error> {
+        let (value, captured) = merge_operator::capture_merge_operands(max_operands, || {
+            self.get_pinned_cf_opt(cf, key, readopts)
+        });
+        unmerged_value(value?, captured)
+    }
+
     /// Return the values associated with the given keys.
     pub fn multi_get<K, I>(&self, keys: I) -> Vec<Result<Option<Vec<u8>>, Error>>
     where
@@ -1562,6 +1637,105 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         DBRawIteratorWithThreadMode::new_cf(self, cf_handle.inner(), readopts)
     }
 
+    /// Opens an iterator over several column families which yields each key once,
+    /// using the default read options. See [`CoalescingIteratorWithThreadMode`].
+    pub fn coalescing_iterator_cf<'a: 'b, 'b, 'c, I, W>(
+        &'a self,
+        cfs: I,
+        mode: IteratorMode,
+    ) -> Result<CoalescingIteratorWithThreadMode<'b, Self>, Error>
+    where
+        I: IntoIterator<Item = &'c W>,
+        W: AsColumnFamilyRef + 'c,
+    {
+        self.coalescing_iterator_cf_opt(cfs, ReadOptions::default(), mode)
+    }
+
+    /// Opens an iterator over several column families which yields each key once,
+    /// using the given read options. See [`CoalescingIteratorWithThreadMode`].
+    pub fn coalescing_iterator_cf_opt<'a: 'b, 'b, 'c, I, W>(
+        &'a self,
+        cfs: I,
+        readopts: ReadOptions,
+        mode: IteratorMode,
+    ) -> Result<CoalescingIteratorWithThreadMode<'b, Self>, Error>
+    where
+        I: IntoIterator<Item = &'c W>,
+        W: AsColumnFamilyRef + 'c,
+    {
+        let iters = self.create_iterators_cf(cfs, &readopts)?;
+        Ok(CoalescingIteratorWithThreadMode::new(iters, readopts, mode))
+    }
+
+    /// Opens an iterator over several column families which yields each key once
+    /// together with its value in every column family, using the default read
+    /// options. See [`AttributeGroupIteratorWithThreadMode`].
+    pub fn attribute_group_iterator_cf<'a: 'b, 'b, 'c, I, W>(
+        &'a self,
+        cfs: I,
+        mode: IteratorMode,
+    ) -> Result<AttributeGroupIteratorWithThreadMode<'b, Self>, Error>
+    where
+        I: IntoIterator<Item = &'c W>,
+        W: AsColumnFamilyRef + 'c,
+    {
+        self.attribute_group_iterator_cf_opt(cfs, ReadOptions::default(), mode)
+    }
+
+    /// Opens an iterator over several column families which yields each key once
+    /// together with its value in every column family, using the given read
+    /// options. See [`AttributeGroupIteratorWithThreadMode`].
+    pub fn attribute_group_iterator_cf_opt<'a: 'b, 'b, 'c, I, W>(
+        &'a self,
+        cfs: I,
+        readopts: ReadOptions,
+        mode: IteratorMode,
+    ) -> Result<AttributeGroupIteratorWithThreadMode<'b, Self>, Error>
+    where
+        I: IntoIterator<Item = &'c W>,
+        W: AsColumnFamilyRef + 'c,
+    {
+        let iters = self.create_iterators_cf(cfs, &readopts)?;
+        Ok(AttributeGroupIteratorWithThreadMode::new(
+            iters, readopts, mode,
+        ))
+    }
+
+    /// Creates one raw iterator per column family, all reading from the same
+    /// consistent view of the database.
+    fn create_iterators_cf<'a: 'b, 'b, 'c, I, W>(
+        &'a self,
+        cfs: I,
+        readopts: &ReadOptions,
+    ) -> Result<Vec<DBRawIteratorWithThreadMode<'b, Self>>, Error>
+    where
+        I: IntoIterator<Item = &'c W>,
+        W: AsColumnFamilyRef + 'c,
+    {
+        let mut cfs: Vec<_> = cfs.into_iter().map(AsColumnFamilyRef::inner).collect();
+        if cfs.is_empty() {
+            return Err(Error::new(
+                "At least one column family is required".to_owned(),
+            ));
+        }
+        let mut iters: Vec<*mut ffi::rocksdb_iterator_t> = vec![ptr::null_mut(); cfs.len()];
+        unsafe {
+            ffi_try!(ffi::rocksdb_create_iterators(
+                self.inner.inner(),
+                readopts.inner,
+                cfs.as_mut_ptr(),
+                iters.as_mut_ptr(),
+                cfs.len(),
+            ));
+        }
+        // The bounds and timestamps of `readopts` are owned by the caller's
+        // multi-cf iterator, so each child only holds empty options.
+        Ok(iters
+            .into_iter()
+            .map(|inner| DBRawIteratorWithThreadMode::from_inner(inner, ReadOptions::default()))
+            .collect())
+    }
+
     pub fn snapshot(&self) -> SnapshotWithThreadMode<Self> {
         SnapshotWithThreadMode::<Self>::new(self)
     }
@@ -1834,6 +2008,112 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         }
     }
 
+    /// Remove the database entry for "key" with WriteOptions.
+    ///
+    /// Unlike [`delete_opt`](Self::delete_opt), the tombstone is dropped together
+    /// with the value it shadows once both meet in a compaction. The key must
+    /// have been written exactly once since its previous deletion and must not
+    /// have been overwritten or merged; otherwise the behavior is undefined.
+    pub fn single_delete_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_singledelete(
+                self.inner.inner(),
+                writeopts.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            ));
+            Ok(())
+        }
+    }
+
+    /// Single delete in a specific column family with WriteOptions.
+    ///
+    /// See [`single_delete_opt`](Self::single_delete_opt) for the requirements
+    /// on the key.
+    pub fn single_delete_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_singledelete_cf(
+                self.inner.inner(),
+                writeopts.inner,
+                cf.inner(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            ));
+            Ok(())
+        }
+    }
+
+    /// Single delete with timestamp with WriteOptions.
+    /// Takes an additional argument `ts` as the timestamp.
+    /// Note: the DB must be opened with user defined timestamp enabled.
+    pub fn single_delete_with_ts_opt<K, S>(
+        &self,
+        key: K,
+        ts: S,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        S: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let ts = ts.as_ref();
+        unsafe {
+            ffi_try!(ffi::rocksdb_singledelete_with_ts(
+                self.inner.inner(),
+                writeopts.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                ts.as_ptr() as *const c_char,
+                ts.len() as size_t,
+            ));
+            Ok(())
+        }
+    }
+
+    /// Single delete with timestamp in a specific column family with WriteOptions.
+    /// Takes an additional argument `ts` as the timestamp.
+    /// Note: the DB must be opened with user defined timestamp enabled.
+    pub fn single_delete_cf_with_ts_opt<K, S>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        ts: S,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        S: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let ts = ts.as_ref();
+        unsafe {
+            ffi_try!(ffi::rocksdb_singledelete_cf_with_ts(
+                self.inner.inner(),
+                writeopts.inner,
+                cf.inner(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                ts.as_ptr() as *const c_char,
+                ts.len() as size_t,
+            ));
+            Ok(())
+        }
+    }
+
     pub fn put<K, V>(&self, key: K, value: V) -> Result<(), Error>
     where
         K: AsRef<[u8]>,
@@ -1948,6 +2228,44 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         self.delete_cf_with_ts_opt(cf, key.as_ref(), ts.as_ref(), &WriteOptions::default())
     }
 
+    /// Remove the database entry for "key", which must have been written only once.
+    ///
+    /// See [`single_delete_opt`](Self::single_delete_opt) for details.
+    pub fn single_delete<K: AsRef<[u8]>>(&self, key: K) -> Result<(), Error> {
+        self.single_delete_opt(key.as_ref(), &WriteOptions::default())
+    }
+
+    pub fn single_delete_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+    ) -> Result<(), Error> {
+        self.single_delete_cf_opt(cf, key.as_ref(), &WriteOptions::default())
+    }
+
+    /// Single delete with timestamp.
+    /// Takes an additional argument `ts` as the timestamp.
+    /// Note: the DB must be opened with user defined timestamp enabled.
+    pub fn single_delete_with_ts<K: AsRef<[u8]>, S: AsRef<[u8]>>(
+        &self,
+        key: K,
+        ts: S,
+    ) -> Result<(), Error> {
+        self.single_delete_with_ts_opt(key.as_ref(), ts.as_ref(), &WriteOptions::default())
+    }
+
+    /// Single delete with timestamp in a specific column family.
+    /// Takes an additional argument `ts` as the timestamp.
+    /// Note: the DB must be opened with user defined timestamp enabled.
+    pub fn single_delete_cf_with_ts<K: AsRef<[u8]>, S: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        ts: S,
+    ) -> Result<(), Error> {
+        self.single_delete_cf_with_ts_opt(cf, key.as_ref(), ts.as_ref(), &WriteOptions::default())
+    }
+
     /// Runs a manual compaction on the Range of keys given. This is not likely to be needed for typical usage.
     pub fn compact_range<S: AsRef<[u8]>, E: AsRef<[u8]>>(&self, start: Option<S>, end: Option<E>) {
         unsafe {
@@ -2051,6 +2369,30 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         Ok(())
     }
 
+    /// Closes the database and reports any error hit while shutting it down.
+    ///
+    /// Dropping the database closes it as well, but cannot return a failure.
+    /// This waits for all flush and compaction jobs to finish, flushes the
+    /// writes made with the WAL disabled unless `avoid_flush_during_shutdown`
+    /// is set, and then closes the database. Snapshots and iterators borrow
+    /// the database, so none can be outstanding once it is consumed here.
+    ///
+    /// Unlike dropping, this first flushes and syncs the WAL, so a failed
+    /// final WAL sync is returned instead of lost. Read-only and secondary
+    /// instances have no WAL to sync.
+    ///
+    /// After [`cancel_all_background_work`](Self::cancel_all_background_work),
+    /// RocksDB refuses to wait for compactions and this returns a
+    /// `ShutdownInProgress` error. The database is then closed as on drop and
+    /// the status of that close is lost, as the C API has no other way to
+    /// report it.
+    pub fn close(mut self) -> Result<(), Error> {
+        // RocksDB requires every column family handle to be released before
+        // the database is closed.
+        self.cfs.drop_all_cfs_internal();
+        close_db(self.inner.inner())
+    }
+
     pub fn set_options(&self, opts: &[(&str, &str)]) -> Result<(), Error> {
         let copts = convert_options(opts)?;
         let cnames: Vec<*const c_char> = copts.iter().map(|opt| opt.0.as_ptr()).collect();
@@ -2390,18 +2732,7 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
     pub fn get_column_family_metadata(&self) -> ColumnFamilyMetaData {
         unsafe {
             let ptr = ffi::rocksdb_get_column_family_metadata(self.inner.inner());
-
-            let metadata = ColumnFamilyMetaData {
-                size: ffi::rocksdb_column_family_metadata_get_size(ptr),
-                name: from_cstr(ffi::rocksdb_column_family_metadata_get_name(ptr)),
-                file_count: ffi::rocksdb_column_family_metadata_get_file_count(ptr),
-            };
-
-            // destroy
-            ffi::rocksdb_column_family_metadata_destroy(ptr);
-
-            // return
-            metadata
+            ColumnFamilyMetaData::from_c(ptr)
         }
     }
 
@@ -2412,18 +2743,7 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
     ) -> ColumnFamilyMetaData {
         unsafe {
             let ptr = ffi::rocksdb_get_column_family_metadata_cf(self.inner.inner(), cf.inner());
-
-            let metadata = ColumnFamilyMetaData {
-                size: ffi::rocksdb_column_family_metadata_get_size(ptr),
-                name: from_cstr(ffi::rocksdb_column_family_metadata_get_name(ptr)),
-                file_count: ffi::rocksdb_column_family_metadata_get_file_count(ptr),
-            };
-
-            // destroy
-            ffi::rocksdb_column_family_metadata_destroy(ptr);
-
-            // return
-            metadata
+            ColumnFamilyMetaData::from_c(ptr)
         }
     }
 
@@ -2528,6 +2848,28 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         }
     }
 
+    /// Aborts the running manual compactions and makes new ones return right
+    /// away without compacting, until [`enable_manual_compaction`] is called.
+    /// Waits for the running manual compactions to stop. Calls nest, so each
+    /// one needs a matching [`enable_manual_compaction`].
+    ///
+    /// Automatic compactions are not affected.
+    ///
+    /// [`enable_manual_compaction`]: Self::enable_manual_compaction
+    pub fn disable_manual_compaction(&self) {
+        unsafe {
+            ffi::rocksdb_disable_manual_compaction(self.inner.inner());
+        }
+    }
+
+    /// Allows manual compactions again after
+    /// [`disable_manual_compaction`](Self::disable_manual_compaction).
+    pub fn enable_manual_compaction(&self) {
+        unsafe {
+            ffi::rocksdb_enable_manual_compaction(self.inner.inner());
+        }
+    }
+
     fn drop_column_family<C>(
         &self,
         cf_inner: *mut ffi::rocksdb_column_family_handle_t,
@@ -2685,6 +3027,120 @@ pub struct ColumnFamilyMetaData {
     pub name: String,
     // The number of files in this column family.
     pub file_count: usize,
+    // The metadata of each level, starting from level 0.
+    pub levels: Vec<LevelMetaData>,
+}
+
+impl ColumnFamilyMetaData {
+    /// Copies the metadata and destroys `ptr`.
+    unsafe fn from_c(ptr: *mut ffi::rocksdb_column_family_metadata_t) -> Self {
+        unsafe {
+            let level_count = ffi::rocksdb_column_family_metadata_get_level_count(ptr);
+            let levels = (0..level_count)
+                .map(|i| {
+                    LevelMetaData::from_c(ffi::rocksdb_column_family_metadata_get_level_metadata(
+                        ptr, i,
+                    ))
+                })
+                .collect();
+
+            let metadata = ColumnFamilyMetaData {
+                size: ffi::rocksdb_column_family_metadata_get_size(ptr),
+                name: from_cstr_and_free(ffi::rocksdb_column_family_metadata_get_name(ptr)),
+                file_count: ffi::rocksdb_column_family_metadata_get_file_count(ptr),
+                levels,
+            };
+
+            ffi::rocksdb_column_family_metadata_destroy(ptr);
+            metadata
+        }
+    }
+}
+
+/// The metadata that describes a level of a column family.
+#[derive(Debug, Clone)]
+pub struct LevelMetaData {
+    /// The level number
+    pub level: i32,
+    /// The size of this level in bytes, which is equal to the sum of the file
+    /// size of its files
+    pub size: u64,
+    /// The metadata of the SST files in this level
+    pub files: Vec<SstFileMetaData>,
+}
+
+impl LevelMetaData {
+    /// Copies the metadata and destroys `ptr`.
+    unsafe fn from_c(ptr: *mut ffi::rocksdb_level_metadata_t) -> Self {
+        unsafe {
+            let file_count = ffi::rocksdb_level_metadata_get_file_count(ptr);
+            let files = (0..file_count)
+                .map(|i| {
+                    SstFileMetaData::from_c(ffi::rocksdb_level_metadata_get_sst_file_metadata(
+                        ptr, i,
+                    ))
+                })
+                .collect();
+
+            let metadata = LevelMetaData {
+                level: ffi::rocksdb_level_metadata_get_level(ptr),
+                size: ffi::rocksdb_level_metadata_get_size(ptr),
+                files,
+            };
+
+            ffi::rocksdb_level_metadata_destroy(ptr);
+            metadata
+        }
+    }
+}
+
+/// The metadata that describes a SST file of a column family level.
+#[derive(Debug, Clone)]
+pub struct SstFileMetaData {
+    /// The number of the file, parsed from its name
+    pub file_number: u64,
+    /// Name of the file, relative to `directory`
+    pub relative_filename: String,
+    /// Directory containing the file
+    pub directory: String,
+    /// Size of the file in bytes
+    pub size: u64,
+    /// Smallest user defined key in the file
+    pub smallest_key: Vec<u8>,
+    /// Largest user defined key in the file
+    pub largest_key: Vec<u8>,
+}
+
+impl SstFileMetaData {
+    /// Copies the metadata and destroys `ptr`.
+    unsafe fn from_c(ptr: *mut ffi::rocksdb_sst_file_metadata_t) -> Self {
+        unsafe {
+            let relative_filename =
+                from_cstr_and_free(ffi::rocksdb_sst_file_metadata_get_relative_filename(ptr));
+            let file_number = relative_filename
+                .strip_suffix(".sst")
+                .and_then(|number| number.parse().ok())
+                .unwrap_or_default();
+
+            let mut key_size: usize = 0;
+            let smallest_key = ffi::rocksdb_sst_file_metadata_get_smallestkey(ptr, &mut key_size);
+            let smallest_key = CSlice::from_raw_parts(smallest_key, key_size);
+            let largest_key = ffi::rocksdb_sst_file_metadata_get_largestkey(ptr, &mut key_size);
+            let largest_key = CSlice::from_raw_parts(largest_key, key_size);
+
+            let metadata = SstFileMetaData {
+                file_number,
+                relative_filename,
+                directory: from_cstr_and_free(ffi::rocksdb_sst_file_metadata_get_directory(ptr)),
+                size: ffi::rocksdb_sst_file_metadata_get_size(ptr),
+                smallest_key: smallest_key.as_ref().to_vec(),
+                largest_key: largest_key.as_ref().to_vec(),
+            };
+
+            ffi::rocksdb_sst_file_metadata_destroy(ptr);
+            metadata
+        }
+    }
 }
 
 /// The metadata that describes a SST file
@@ -2724,6 +3180,53 @@ fn convert_options(opts: &[(&str, &str)]) -> Result<Vec<(CString, CString)>, Err
         .collect()
 }
 
+/// Turns the result of a read made under `capture_merge_operands` into the
+/// unmerged state of the key. A key read without a full merge has no operands.
+fn unmerged_value(
+    value: Option<DBPinnableSlice>,
+    captured: Option<CapturedMerge>,
+) -> Result<Option<UnmergedValue>, Error> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    match captured {
+        Some(CapturedMerge::Unmerged(unmerged)) => Ok(Some(unmerged)),
+        // The status RocksDB's `GetMergeOperands` returns in this case.
+        Some(CapturedMerge::TooManyOperands) => Err(Error::new(
+            "Result incomplete: Insufficient capacity for merge operands".to_owned(),
+        )),
+        None => Ok(Some(UnmergedValue {
+            base: Some(value.to_vec()),
+            operands: Vec::new(),
+        })),
+    }
+}
+
+/// Syncs the WAL of `db` and closes it through `DB::WaitForCompact` with
+/// `close_db` set, the only way the C API reports the status of `DB::Close`.
+/// The handle must still be destroyed afterwards, which then skips the already
+/// finished shutdown.
+pub(crate) fn close_db(db: *mut ffi::rocksdb_t) -> Result<(), Error> {
+    let sync_wal = || -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_flush_wal(db, c_uchar::from(true)));
+        }
+        Ok(())
+    };
+    match sync_wal() {
+        // Read-only and secondary instances have no WAL of their own to sync.
+        Err(e) if e.kind() == ErrorKind::NotSupported => {}
+        result => result?,
+    }
+
+    let opts = WaitForCompactOptions::default();
+    unsafe {
+        ffi::rocksdb_wait_for_compact_options_set_close_db(opts.inner, 1);
+        ffi_try!(ffi::rocksdb_wait_for_compact(db, opts.inner));
+    }
+    Ok(())
+}
+
 pub(crate) fn convert_values(
     values: Vec<*mut c_char>,
     values_sizes: Vec<usize>,
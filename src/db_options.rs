@@ -30,8 +30,10 @@ use crate::{
     },
     db::DBAccess,
     env::Env,
+    event_listener::{self, EventListener},
     ffi,
     ffi_util::{from_cstr, to_cpath, CStrLike},
+    logger::{self, Logger, LoggerCallback},
     merge_operator::{
         self, full_merge_callback, partial_merge_callback, MergeFn, MergeOperatorCallback,
     },
@@ -216,6 +218,73 @@ impl Cache {
     }
 }
 
+pub(crate) struct RateLimiterWrapper {
+    pub(crate) inner: NonNull<ffi::rocksdb_ratelimiter_t>,
+}
+
+impl Drop for RateLimiterWrapper {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_ratelimiter_destroy(self.inner.as_ptr());
+        }
+    }
+}
+
+/// The kind of I/O a [`RateLimiter`] applies to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(i32)]
+pub enum RateLimiterMode {
+    ReadsOnly = 0,
+    WritesOnly = 1,
+    AllIo = 2,
+}
+
+/// A rate limiter which can be shared by several [`Options`], so that all the
+/// DBs opened with them draw from the same I/O budget.
+///
+/// # Examples
+///
+/// ```
+/// use rocksdb::{Options, RateLimiter, RateLimiterMode};
+///
+/// let limiter = RateLimiter::new(64 << 20, 100 * 1000, 10, RateLimiterMode::WritesOnly, false);
+/// let mut opts1 = Options::default();
+/// opts1.set_shared_ratelimiter(&limiter);
+/// let mut opts2 = Options::default();
+/// opts2.set_shared_ratelimiter(&limiter);
+/// ```
+#[derive(Clone)]
+pub struct RateLimiter(pub(crate) Arc<RateLimiterWrapper>);
+
+impl RateLimiter {
+    /// Creates a rate limiter.
+    ///
+    /// `rate_bytes_per_sec` is the total budget shared by every DB using this
+    /// limiter. `refill_period_us` controls how often tokens are refilled and
+    /// `fairness` is the chance (1 / fairness) that low priority requests get
+    /// served before high priority ones. With `auto_tuned`, the rate is
+    /// adjusted dynamically below `rate_bytes_per_sec` based on recent demand.
+    pub fn new(
+        rate_bytes_per_sec: i64,
+        refill_period_us: i64,
+        fairness: i32,
+        mode: RateLimiterMode,
+        auto_tuned: bool,
+    ) -> RateLimiter {
+        let inner = NonNull::new(unsafe {
+            ffi::rocksdb_ratelimiter_create_with_mode(
+                rate_bytes_per_sec,
+                refill_period_us,
+                fairness,
+                mode as c_int,
+                auto_tuned,
+            )
+        })
+        .unwrap();
+        RateLimiter(Arc::new(RateLimiterWrapper { inner }))
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct OptionsMustOutliveDB {
     env: Option<Env>,
@@ -223,6 +292,7 @@ pub(crate) struct OptionsMustOutliveDB {
     blob_cache: Option<Cache>,
     block_based: Option<BlockBasedOptionsMustOutliveDB>,
     write_buffer_manager: Option<WriteBufferManager>,
+    logger: Option<Arc<LoggerCallback>>,
 }
 
 impl OptionsMustOutliveDB {
@@ -236,6 +306,7 @@ impl OptionsMustOutliveDB {
                 .as_ref()
                 .map(BlockBasedOptionsMustOutliveDB::clone),
             write_buffer_manager: self.write_buffer_manager.clone(),
+            logger: self.logger.clone(),
         }
     }
 }
@@ -428,6 +499,7 @@ unsafe impl Send for IngestExternalFileOptions {}
 unsafe impl Send for CacheWrapper {}
 unsafe impl Send for CompactOptions {}
 unsafe impl Send for WriteBufferManagerWrapper {}
+unsafe impl Send for RateLimiterWrapper {}
 
 // Sync is similarly safe for many types because they do not expose interior mutability, and their
 // use within the rocksdb library is generally behind a const reference
@@ -442,6 +514,7 @@ unsafe impl Sync for IngestExternalFileOptions {}
 unsafe impl Sync for CacheWrapper {}
 unsafe impl Sync for CompactOptions {}
 unsafe impl Sync for WriteBufferManagerWrapper {}
+unsafe impl Sync for RateLimiterWrapper {}
 
 impl Drop for Options {
     fn drop(&mut self) {
@@ -1957,6 +2030,25 @@ impl Options {
         }
     }
 
+    /// Sends the info LOG to `logger` instead of the LOG file in the DB
+    /// directory. Only lines at or above `level` are passed to the logger;
+    /// this replaces [`Options::set_log_level`] for the given logger.
+    ///
+    /// See [`Logger`] for an example.
+    pub fn set_logger<L: Logger>(&mut self, level: LogLevel, logger: L) {
+        let cb: Arc<LoggerCallback> = Arc::new(Box::new(logger));
+        unsafe {
+            let logger = ffi::rocksdb_logger_create_callback_logger(
+                level as c_int,
+                Some(logger::logv_callback),
+                Arc::as_ptr(&cb) as *mut c_void,
+            );
+            ffi::rocksdb_options_set_info_log(self.inner, logger);
+            ffi::rocksdb_logger_destroy(logger);
+        }
+        self.outlive.logger = Some(cb);
+    }
+
     /// Allows OS to incrementally sync files to disk while they are being
     /// written, asynchronously, in the background. This operation can be used
     /// to smooth out write I/Os over time. Users shouldn't rely on it for
@@ -3390,6 +3482,17 @@ impl Options {
         }
     }
 
+    /// Sets a rate limiter which is shared with every other [`Options`] it is
+    /// set on, unlike [`Options::set_ratelimiter`] which creates a limiter for
+    /// these options alone.
+    ///
+    /// Default: disable
+    pub fn set_shared_ratelimiter(&mut self, ratelimiter: &RateLimiter) {
+        unsafe {
+            ffi::rocksdb_options_set_ratelimiter(self.inner, ratelimiter.0.inner.as_ptr());
+        }
+    }
+
     /// Sets the maximal size of the info log file.
     ///
     /// If the log file is larger than `max_log_file_size`, a new info log file
@@ -3648,6 +3751,35 @@ impl Options {
         }
     }
 
+    /// Registers an [`EventListener`] that is notified of flushes, compactions,
+    /// external file ingestion, write stalls, sealed memtables and background
+    /// errors. Several listeners can be added; they are called in the order
+    /// they were added.
+    ///
+    /// See the [`event_listener`](crate::event_listener) module for details.
+    pub fn add_event_listener<L: EventListener>(&mut self, listener: L) {
+        let cb = Box::new(listener);
+
+        unsafe {
+            let listener = ffi::rocksdb_eventlistener_create(
+                Box::into_raw(cb).cast::<c_void>(),
+                Some(event_listener::destructor_callback::<L>),
+                Some(event_listener::on_flush_begin_callback::<L>),
+                Some(event_listener::on_flush_completed_callback::<L>),
+                Some(event_listener::on_compaction_begin_callback::<L>),
+                Some(event_listener::on_compaction_completed_callback::<L>),
+                Some(event_listener::on_subcompaction_begin_callback::<L>),
+                Some(event_listener::on_subcompaction_completed_callback::<L>),
+                Some(event_listener::on_external_file_ingested_callback::<L>),
+                Some(event_listener::on_background_error_callback::<L>),
+                Some(event_listener::on_stall_conditions_changed_callback::<L>),
+                Some(event_listener::on_memtable_sealed_callback::<L>),
+            );
+            // The options take ownership of the listener.
+            ffi::rocksdb_options_add_eventlistener(self.inner, listener);
+        }
+    }
+
     /// <https://github.com/facebook/rocksdb/wiki/Write-Buffer-Manager>
     /// Write buffer manager helps users control the total memory used by memtables across multiple column families and/or DB instances.
     /// Users can enable this control by 2 ways:
@@ -4321,6 +4453,22 @@ impl IngestExternalFileOptions {
             ffi::rocksdb_ingestexternalfileoptions_set_ingest_behind(self.inner, c_uchar::from(v));
         }
     }
+
+    /// Set to true if the files must be ingested into the last level.
+    /// Ingestion fails with a `TryAgain` error if a file cannot fit there,
+    /// in which case the last level should be cleared in the overlapping range
+    /// before trying again.
+    /// `ingest_behind` takes precedence over this option.
+    ///
+    /// Default: false
+    pub fn set_fail_if_not_bottommost_level(&mut self, v: bool) {
+        unsafe {
+            ffi::rocksdb_ingestexternalfileoptions_set_fail_if_not_bottommost_level(
+                self.inner,
+                c_uchar::from(v),
+            );
+        }
+    }
 }
 
 impl Default for IngestExternalFileOptions {
@@ -0,0 +1,506 @@
+//! Callbacks for RocksDB background events
+//!
+//! An [`EventListener`] registered with [`Options::add_event_listener`] is
+//! notified of flushes, compactions, external file ingestion, write stalls,
+//! sealed memtables and background errors of every DB opened with these
+//! options.
+//!
+//! The callbacks run on RocksDB's background threads (or on the thread
+//! performing the operation), so they should return quickly and must not
+//! call back into the DB in a way that could wait for the operation which
+//! triggered them.
+//!
+//! [`Options::add_event_listener`]: crate::Options::add_event_listener
+
+use crate::{ffi, Error};
+use libc::{c_char, c_int, c_void, size_t};
+use std::slice;
+
+/// Receives notifications about RocksDB background events.
+///
+/// Every method has an empty default implementation, so a listener only
+/// implements the events it is interested in.
+#[allow(unused_variables)]
+pub trait EventListener: Send + Sync + 'static {
+    /// Called before a flush job starts.
+    fn on_flush_begin(&self, info: &FlushJobInfo) {}
+
+    /// Called after a flush job has finished and its output file has been
+    /// installed.
+    fn on_flush_completed(&self, info: &FlushJobInfo) {}
+
+    /// Called before a compaction job starts.
+    fn on_compaction_begin(&self, info: &CompactionJobInfo) {}
+
+    /// Called after a compaction job has finished, whether it succeeded or
+    /// not, see [`CompactionJobInfo::status`].
+    fn on_compaction_completed(&self, info: &CompactionJobInfo) {}
+
+    /// Called before a subcompaction of a compaction job starts.
+    fn on_subcompaction_begin(&self, info: &SubcompactionJobInfo) {}
+
+    /// Called after a subcompaction of a compaction job has finished.
+    fn on_subcompaction_completed(&self, info: &SubcompactionJobInfo) {}
+
+    /// Called after an external file has been ingested.
+    fn on_external_file_ingested(&self, info: &ExternalFileIngestionInfo) {}
+
+    /// Called when a background operation fails and the DB is about to stop
+    /// accepting writes. Calling [`BackgroundError::suppress`] keeps the DB
+    /// writable.
+    fn on_background_error(&self, reason: BackgroundErrorReason, error: &mut BackgroundError) {}
+
+    /// Called when the write stall condition of a column family changes.
+    fn on_stall_conditions_changed(&self, info: &WriteStallInfo) {}
+
+    /// Called when a memtable becomes immutable and is queued for flush.
+    fn on_memtable_sealed(&self, info: &MemTableInfo) {}
+}
+
+unsafe fn str_from_raw(ptr: *const c_char, len: size_t) -> String {
+    let bytes = unsafe { slice::from_raw_parts(ptr as *const u8, len) };
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// Information about a flush job, see [`EventListener::on_flush_begin`] and
+/// [`EventListener::on_flush_completed`].
+pub struct FlushJobInfo {
+    inner: *const ffi::rocksdb_flushjobinfo_t,
+}
+
+impl FlushJobInfo {
+    /// The name of the column family being flushed.
+    pub fn cf_name(&self) -> String {
+        let mut len: size_t = 0;
+        unsafe { str_from_raw(ffi::rocksdb_flushjobinfo_cf_name(self.inner, &mut len), len) }
+    }
+
+    /// The path of the SST file the flush writes.
+    pub fn file_path(&self) -> String {
+        let mut len: size_t = 0;
+        unsafe {
+            str_from_raw(
+                ffi::rocksdb_flushjobinfo_file_path(self.inner, &mut len),
+                len,
+            )
+        }
+    }
+
+    /// Whether the flush was triggered because writes were slowed down.
+    pub fn triggered_writes_slowdown(&self) -> bool {
+        unsafe { ffi::rocksdb_flushjobinfo_triggered_writes_slowdown(self.inner) != 0 }
+    }
+
+    /// Whether the flush was triggered because writes were stopped.
+    pub fn triggered_writes_stop(&self) -> bool {
+        unsafe { ffi::rocksdb_flushjobinfo_triggered_writes_stop(self.inner) != 0 }
+    }
+
+    /// The smallest sequence number in the flushed file.
+    pub fn smallest_seqno(&self) -> u64 {
+        unsafe { ffi::rocksdb_flushjobinfo_smallest_seqno(self.inner) }
+    }
+
+    /// The largest sequence number in the flushed file.
+    pub fn largest_seqno(&self) -> u64 {
+        unsafe { ffi::rocksdb_flushjobinfo_largest_seqno(self.inner) }
+    }
+}
+
+/// Information about a compaction job, see
+/// [`EventListener::on_compaction_begin`] and
+/// [`EventListener::on_compaction_completed`].
+pub struct CompactionJobInfo {
+    inner: *const ffi::rocksdb_compactionjobinfo_t,
+}
+
+impl CompactionJobInfo {
+    /// The result of the compaction job.
+    pub fn status(&self) -> Result<(), Error> {
+        unsafe { ffi_try!(ffi::rocksdb_compactionjobinfo_status(self.inner)) };
+        Ok(())
+    }
+
+    /// The name of the column family being compacted.
+    pub fn cf_name(&self) -> String {
+        let mut len: size_t = 0;
+        unsafe {
+            str_from_raw(
+                ffi::rocksdb_compactionjobinfo_cf_name(self.inner, &mut len),
+                len,
+            )
+        }
+    }
+
+    /// The paths of the compaction input files.
+    pub fn input_files(&self) -> Vec<String> {
+        let count = unsafe { ffi::rocksdb_compactionjobinfo_input_files_count(self.inner) };
+        (0..count)
+            .map(|i| {
+                let mut len: size_t = 0;
+                unsafe {
+                    str_from_raw(
+                        ffi::rocksdb_compactionjobinfo_input_file_at(self.inner, i, &mut len),
+                        len,
+                    )
+                }
+            })
+            .collect()
+    }
+
+    /// The paths of the compaction output files.
+    pub fn output_files(&self) -> Vec<String> {
+        let count = unsafe { ffi::rocksdb_compactionjobinfo_output_files_count(self.inner) };
+        (0..count)
+            .map(|i| {
+                let mut len: size_t = 0;
+                unsafe {
+                    str_from_raw(
+                        ffi::rocksdb_compactionjobinfo_output_file_at(self.inner, i, &mut len),
+                        len,
+                    )
+                }
+            })
+            .collect()
+    }
+
+    /// The level of the compaction input files with the smallest level number.
+    pub fn base_input_level(&self) -> i32 {
+        unsafe { ffi::rocksdb_compactionjobinfo_base_input_level(self.inner) }
+    }
+
+    /// The level the compaction writes to.
+    pub fn output_level(&self) -> i32 {
+        unsafe { ffi::rocksdb_compactionjobinfo_output_level(self.inner) }
+    }
+
+    /// The reason of the compaction, as the numeric value of RocksDB's
+    /// `CompactionReason` enum.
+    pub fn compaction_reason(&self) -> u32 {
+        unsafe { ffi::rocksdb_compactionjobinfo_compaction_reason(self.inner) }
+    }
+
+    /// The number of input files.
+    pub fn num_input_files(&self) -> usize {
+        unsafe { ffi::rocksdb_compactionjobinfo_num_input_files(self.inner) }
+    }
+
+    /// The number of input files that were already in the output level.
+    pub fn num_input_files_at_output_level(&self) -> usize {
+        unsafe { ffi::rocksdb_compactionjobinfo_num_input_files_at_output_level(self.inner) }
+    }
+
+    /// The time the compaction took in microseconds. Only set on completion.
+    pub fn elapsed_micros(&self) -> u64 {
+        unsafe { ffi::rocksdb_compactionjobinfo_elapsed_micros(self.inner) }
+    }
+
+    /// The number of corrupt keys found. Only set on completion.
+    pub fn num_corrupt_keys(&self) -> u64 {
+        unsafe { ffi::rocksdb_compactionjobinfo_num_corrupt_keys(self.inner) }
+    }
+
+    /// The number of records read. Only set on completion.
+    pub fn input_records(&self) -> u64 {
+        unsafe { ffi::rocksdb_compactionjobinfo_input_records(self.inner) }
+    }
+
+    /// The number of records written. Only set on completion.
+    pub fn output_records(&self) -> u64 {
+        unsafe { ffi::rocksdb_compactionjobinfo_output_records(self.inner) }
+    }
+
+    /// The size of the input files in bytes. Only set on completion.
+    pub fn total_input_bytes(&self) -> u64 {
+        unsafe { ffi::rocksdb_compactionjobinfo_total_input_bytes(self.inner) }
+    }
+
+    /// The size of the output files in bytes. Only set on completion.
+    pub fn total_output_bytes(&self) -> u64 {
+        unsafe { ffi::rocksdb_compactionjobinfo_total_output_bytes(self.inner) }
+    }
+}
+
+/// Information about a subcompaction, see
+/// [`EventListener::on_subcompaction_begin`] and
+/// [`EventListener::on_subcompaction_completed`].
+pub struct SubcompactionJobInfo {
+    inner: *const ffi::rocksdb_subcompactionjobinfo_t,
+}
+
+impl SubcompactionJobInfo {
+    /// The result of the subcompaction.
+    pub fn status(&self) -> Result<(), Error> {
+        unsafe { ffi_try!(ffi::rocksdb_subcompactionjobinfo_status(self.inner)) };
+        Ok(())
+    }
+
+    /// The name of the column family being compacted.
+    pub fn cf_name(&self) -> String {
+        let mut len: size_t = 0;
+        unsafe {
+            str_from_raw(
+                ffi::rocksdb_subcompactionjobinfo_cf_name(self.inner, &mut len),
+                len,
+            )
+        }
+    }
+
+    /// The id of the thread running the subcompaction.
+    pub fn thread_id(&self) -> u64 {
+        unsafe { ffi::rocksdb_subcompactionjobinfo_thread_id(self.inner) }
+    }
+
+    /// The level of the input files with the smallest level number.
+    pub fn base_input_level(&self) -> i32 {
+        unsafe { ffi::rocksdb_subcompactionjobinfo_base_input_level(self.inner) }
+    }
+
+    /// The level the subcompaction writes to.
+    pub fn output_level(&self) -> i32 {
+        unsafe { ffi::rocksdb_subcompactionjobinfo_output_level(self.inner) }
+    }
+}
+
+/// Information about an ingested external file, see
+/// [`EventListener::on_external_file_ingested`].
+pub struct ExternalFileIngestionInfo {
+    inner: *const ffi::rocksdb_externalfileingestioninfo_t,
+}
+
+impl ExternalFileIngestionInfo {
+    /// The name of the column family the file was ingested into.
+    pub fn cf_name(&self) -> String {
+        let mut len: size_t = 0;
+        unsafe {
+            str_from_raw(
+                ffi::rocksdb_externalfileingestioninfo_cf_name(self.inner, &mut len),
+                len,
+            )
+        }
+    }
+
+    /// The path of the file inside the DB directory.
+    pub fn internal_file_path(&self) -> String {
+        let mut len: size_t = 0;
+        unsafe {
+            str_from_raw(
+                ffi::rocksdb_externalfileingestioninfo_internal_file_path(self.inner, &mut len),
+                len,
+            )
+        }
+    }
+}
+
+/// The write stall condition of a column family.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WriteStallCondition {
+    Delayed,
+    Stopped,
+    Normal,
+}
+
+impl WriteStallCondition {
+    unsafe fn from_c(ptr: *const ffi::rocksdb_writestallcondition_t) -> Self {
+        // The C API hands out a pointer to the C++ `WriteStallCondition` enum.
+        match unsafe { *(ptr as *const c_int) } {
+            0 => Self::Delayed,
+            1 => Self::Stopped,
+            _ => Self::Normal,
+        }
+    }
+}
+
+/// Information about a write stall condition change, see
+/// [`EventListener::on_stall_conditions_changed`].
+pub struct WriteStallInfo {
+    inner: *const ffi::rocksdb_writestallinfo_t,
+}
+
+impl WriteStallInfo {
+    /// The name of the column family.
+    pub fn cf_name(&self) -> String {
+        let mut len: size_t = 0;
+        unsafe {
+            str_from_raw(
+                ffi::rocksdb_writestallinfo_cf_name(self.inner, &mut len),
+                len,
+            )
+        }
+    }
+
+    /// The new write stall condition.
+    pub fn cur(&self) -> WriteStallCondition {
+        unsafe { WriteStallCondition::from_c(ffi::rocksdb_writestallinfo_cur(self.inner)) }
+    }
+
+    /// The previous write stall condition.
+    pub fn prev(&self) -> WriteStallCondition {
+        unsafe { WriteStallCondition::from_c(ffi::rocksdb_writestallinfo_prev(self.inner)) }
+    }
+}
+
+/// Information about a sealed memtable, see
+/// [`EventListener::on_memtable_sealed`].
+pub struct MemTableInfo {
+    inner: *const ffi::rocksdb_memtableinfo_t,
+}
+
+impl MemTableInfo {
+    /// The name of the column family the memtable belongs to.
+    pub fn cf_name(&self) -> String {
+        let mut len: size_t = 0;
+        unsafe { str_from_raw(ffi::rocksdb_memtableinfo_cf_name(self.inner, &mut len), len) }
+    }
+
+    /// The sequence number of the first element inserted into the memtable.
+    pub fn first_seqno(&self) -> u64 {
+        unsafe { ffi::rocksdb_memtableinfo_first_seqno(self.inner) }
+    }
+
+    /// The sequence number that is guaranteed to be smaller than or equal to
+    /// the sequence number of any key that could be inserted into the memtable.
+    pub fn earliest_seqno(&self) -> u64 {
+        unsafe { ffi::rocksdb_memtableinfo_earliest_seqno(self.inner) }
+    }
+
+    /// The number of entries in the memtable.
+    pub fn num_entries(&self) -> u64 {
+        unsafe { ffi::rocksdb_memtableinfo_num_entries(self.inner) }
+    }
+
+    /// The number of deletes in the memtable.
+    pub fn num_deletes(&self) -> u64 {
+        unsafe { ffi::rocksdb_memtableinfo_num_deletes(self.inner) }
+    }
+}
+
+/// The operation which caused a background error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BackgroundErrorReason {
+    Flush,
+    Compaction,
+    WriteCallback,
+    MemTable,
+    ManifestWrite,
+    FlushNoWal,
+    ManifestWriteNoWal,
+    Unknown,
+}
+
+impl From<u32> for BackgroundErrorReason {
+    fn from(reason: u32) -> Self {
+        match reason {
+            0 => Self::Flush,
+            1 => Self::Compaction,
+            2 => Self::WriteCallback,
+            3 => Self::MemTable,
+            4 => Self::ManifestWrite,
+            5 => Self::FlushNoWal,
+            6 => Self::ManifestWriteNoWal,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// A background error reported to [`EventListener::on_background_error`].
+pub struct BackgroundError {
+    inner: *mut ffi::rocksdb_status_ptr_t,
+}
+
+impl BackgroundError {
+    /// Clears the error, so that the DB does not switch to read-only mode.
+    pub fn suppress(&mut self) {
+        unsafe { ffi::rocksdb_reset_status(self.inner) };
+    }
+}
+
+pub(crate) unsafe extern "C" fn destructor_callback<L: EventListener>(raw_cb: *mut c_void) {
+    drop(unsafe { Box::from_raw(raw_cb as *mut L) });
+}
+
+pub(crate) unsafe extern "C" fn on_flush_begin_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    _db: *mut ffi::rocksdb_t,
+    info: *const ffi::rocksdb_flushjobinfo_t,
+) {
+    let cb = unsafe { &*(raw_cb as *const L) };
+    cb.on_flush_begin(&FlushJobInfo { inner: info });
+}
+
+pub(crate) unsafe extern "C" fn on_flush_completed_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    _db: *mut ffi::rocksdb_t,
+    info: *const ffi::rocksdb_flushjobinfo_t,
+) {
+    let cb = unsafe { &*(raw_cb as *const L) };
+    cb.on_flush_completed(&FlushJobInfo { inner: info });
+}
+
+pub(crate) unsafe extern "C" fn on_compaction_begin_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    _db: *mut ffi::rocksdb_t,
+    info: *const ffi::rocksdb_compactionjobinfo_t,
+) {
+    let cb = unsafe { &*(raw_cb as *const L) };
+    cb.on_compaction_begin(&CompactionJobInfo { inner: info });
+}
+
+pub(crate) unsafe extern "C" fn on_compaction_completed_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    _db: *mut ffi::rocksdb_t,
+    info: *const ffi::rocksdb_compactionjobinfo_t,
+) {
+    let cb = unsafe { &*(raw_cb as *const L) };
+    cb.on_compaction_completed(&CompactionJobInfo { inner: info });
+}
+
+pub(crate) unsafe extern "C" fn on_subcompaction_begin_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rocksdb_subcompactionjobinfo_t,
+) {
+    let cb = unsafe { &*(raw_cb as *const L) };
+    cb.on_subcompaction_begin(&SubcompactionJobInfo { inner: info });
+}
+
+pub(crate) unsafe extern "C" fn on_subcompaction_completed_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rocksdb_subcompactionjobinfo_t,
+) {
+    let cb = unsafe { &*(raw_cb as *const L) };
+    cb.on_subcompaction_completed(&SubcompactionJobInfo { inner: info });
+}
+
+pub(crate) unsafe extern "C" fn on_external_file_ingested_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    _db: *mut ffi::rocksdb_t,
+    info: *const ffi::rocksdb_externalfileingestioninfo_t,
+) {
+    let cb = unsafe { &*(raw_cb as *const L) };
+    cb.on_external_file_ingested(&ExternalFileIngestionInfo { inner: info });
+}
+
+pub(crate) unsafe extern "C" fn on_background_error_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    reason: u32,
+    status: *mut ffi::rocksdb_status_ptr_t,
+) {
+    let cb = unsafe { &*(raw_cb as *const L) };
+    cb.on_background_error(reason.into(), &mut BackgroundError { inner: status });
+}
+
+pub(crate) unsafe extern "C" fn on_stall_conditions_changed_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rocksdb_writestallinfo_t,
+) {
+    let cb = unsafe { &*(raw_cb as *const L) };
+    cb.on_stall_conditions_changed(&WriteStallInfo { inner: info });
+}
+
+pub(crate) unsafe extern "C" fn on_memtable_sealed_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rocksdb_memtableinfo_t,
+) {
+    let cb = unsafe { &*(raw_cb as *const L) };
+    cb.on_memtable_sealed(&MemTableInfo { inner: info });
+}
@@ -24,6 +24,15 @@ pub(crate) unsafe fn from_cstr(ptr: *const c_char) -> String {
     String::from_utf8_lossy(cstr.to_bytes()).into_owned()
 }
 
+/// Like [`from_cstr`], but also frees a string allocated by RocksDB.
+pub(crate) unsafe fn from_cstr_and_free(ptr: *mut c_char) -> String {
+    unsafe {
+        let s = from_cstr(ptr);
+        ffi::rocksdb_free(ptr as *mut c_void);
+        s
+    }
+}
+
 pub(crate) unsafe fn raw_data(ptr: *const c_char, size: usize) -> Option<Vec<u8>> {
     if ptr.is_null() {
         None
@@ -84,6 +84,7 @@
 mod ffi_util;
 
 pub mod backup;
+mod bulk_loader;
 pub mod checkpoint;
 mod column_family;
 pub mod compaction_filter;
@@ -94,8 +95,11 @@ mod db_iterator;
 mod db_options;
 mod db_pinnable_slice;
 mod env;
+pub mod event_listener;
 mod iter_range;
+mod logger;
 pub mod merge_operator;
+mod multi_cf_iterator;
 pub mod perf;
 mod prop_name;
 pub mod properties;
@@ -107,14 +111,15 @@ mod transactions;
 mod write_batch;
 
 pub use crate::{
+    bulk_loader::BulkLoader,
     column_family::{
         AsColumnFamilyRef, BoundColumnFamily, ColumnFamily, ColumnFamilyDescriptor,
         ColumnFamilyRef, ColumnFamilyTtl, DEFAULT_COLUMN_FAMILY_NAME,
     },
     compaction_filter::Decision as CompactionDecision,
     db::{
-        DBAccess, DBCommon, DBWithThreadMode, LiveFile, MultiThreaded, Range, SingleThreaded,
-        ThreadMode, DB,
+        ColumnFamilyMetaData, DBAccess, DBCommon, DBWithThreadMode, LevelMetaData, LiveFile,
+        MultiThreaded, Range, SingleThreaded, SstFileMetaData, ThreadMode, DB,
     },
     db_iterator::{
         DBIterator, DBIteratorWithThreadMode, DBRawIterator, DBRawIteratorWithThreadMode,
@@ -126,18 +131,25 @@ pub use crate::{
         CuckooTableOptions, DBCompactionStyle, DBCompressionType, DBPath, DBRecoveryMode,
         DataBlockIndexType, FifoCompactOptions, FlushOptions, IngestExternalFileOptions,
         KeyEncodingType, LogLevel, LruCacheOptions, MemtableFactory, Options,
-        PlainTableFactoryOptions, ReadOptions, ReadTier, UniversalCompactOptions,
-        UniversalCompactionStopStyle, WaitForCompactOptions, WriteBufferManager, WriteOptions,
+        PlainTableFactoryOptions, RateLimiter, RateLimiterMode, ReadOptions, ReadTier,
+        UniversalCompactOptions, UniversalCompactionStopStyle, WaitForCompactOptions,
+        WriteBufferManager, WriteOptions,
     },
     db_pinnable_slice::DBPinnableSlice,
     env::Env,
+    event_listener::EventListener,
     ffi_util::CStrLike,
     iter_range::{IterateBounds, PrefixRange},
-    merge_operator::MergeOperands,
+    logger::Logger,
+    merge_operator::{MergeOperands, UnmergedValue},
+    multi_cf_iterator::{
+        AttributeGroupIterator, AttributeGroupIteratorWithThreadMode, AttributeGroups,
+        CoalescingIterator, CoalescingIteratorWithThreadMode, KeyAttributeGroups,
+    },
     perf::{PerfContext, PerfMetric, PerfStatsLevel},
     slice_transform::SliceTransform,
     snapshot::{Snapshot, SnapshotWithThreadMode},
-    sst_file_writer::SstFileWriter,
+    sst_file_writer::{ExternalSstFileInfo, SstFileWriter},
     transactions::{
         OptimisticTransactionDB, OptimisticTransactionOptions, Transaction, TransactionDB,
         TransactionDBOptions, TransactionOptions,
@@ -173,6 +185,58 @@ pub enum ErrorKind {
     Unknown,
 }
 
+/// RocksDB error sub-code, giving the cause of some [`ErrorKind`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorSubCode {
+    NoSubCode,
+    MutexTimeout,
+    LockTimeout,
+    LockLimit,
+    NoSpace,
+    Deadlock,
+    StaleFile,
+    MemoryLimit,
+    SpaceLimit,
+    PathNotFound,
+    MergeOperandsInsufficientCapacity,
+    ManualCompactionPaused,
+    TxnNotPrepared,
+    IOFenced,
+    MergeOperatorFailed,
+    MergeOperandThresholdExceeded,
+}
+
+/// The message RocksDB writes after the error kind for each sub-code.
+const ERROR_SUBCODE_MESSAGES: [(&str, ErrorSubCode); 15] = [
+    ("Timeout Acquiring Mutex", ErrorSubCode::MutexTimeout),
+    ("Timeout waiting to lock key", ErrorSubCode::LockTimeout),
+    (
+        "Failed to acquire lock due to max_num_locks limit",
+        ErrorSubCode::LockLimit,
+    ),
+    ("No space left on device", ErrorSubCode::NoSpace),
+    ("Deadlock", ErrorSubCode::Deadlock),
+    ("Stale file handle", ErrorSubCode::StaleFile),
+    ("Memory limit reached", ErrorSubCode::MemoryLimit),
+    ("Space limit reached", ErrorSubCode::SpaceLimit),
+    ("No such file or directory", ErrorSubCode::PathNotFound),
+    (
+        "Insufficient capacity for merge operands",
+        ErrorSubCode::MergeOperandsInsufficientCapacity,
+    ),
+    (
+        "Manual compaction paused",
+        ErrorSubCode::ManualCompactionPaused,
+    ),
+    ("Txn not prepared", ErrorSubCode::TxnNotPrepared),
+    ("IO fenced off", ErrorSubCode::IOFenced),
+    ("Merge operator failed", ErrorSubCode::MergeOperatorFailed),
+    (
+        "Number of operands merged exceeded threshold",
+        ErrorSubCode::MergeOperandThresholdExceeded,
+    ),
+];
+
 /// A simple wrapper round a string, used for errors reported from
 /// ffi calls.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -210,6 +274,37 @@ impl Error {
             _ => ErrorKind::Unknown,
         }
     }
+
+    /// Parse the [`ErrorSubCode`] from error message.
+    ///
+    /// RocksDB writes the sub-code as a fixed message right after the error
+    /// kind, e.g. `Operation timed out: Timeout waiting to lock key`.
+    pub fn subcode(&self) -> ErrorSubCode {
+        if self.kind() == ErrorKind::Unknown {
+            return ErrorSubCode::NoSubCode;
+        }
+        let Some((_, rest)) = self.message.split_once(": ") else {
+            return ErrorSubCode::NoSubCode;
+        };
+        ERROR_SUBCODE_MESSAGES
+            .iter()
+            .find(|(message, _)| rest.starts_with(message))
+            .map_or(ErrorSubCode::NoSubCode, |(_, subcode)| *subcode)
+    }
+
+    /// Whether the failed operation may succeed if it is simply retried.
+    ///
+    /// This is true for contention and timeouts: [`ErrorKind::Busy`],
+    /// [`ErrorKind::TimedOut`] and [`ErrorKind::TryAgain`], which include
+    /// lock timeouts and deadlocks. The C API does not pass on RocksDB's own
+    /// retryable flag or severity of I/O errors, so I/O errors are never
+    /// considered retryable here.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.kind(),
+            ErrorKind::Busy | ErrorKind::TimedOut | ErrorKind::TryAgain
+        )
+    }
 }
 
 impl AsRef<str> for Error {
@@ -245,11 +340,12 @@ mod test {
 
     use super::{
         column_family::UnboundColumnFamily,
-        db_options::{CacheWrapper, WriteBufferManagerWrapper},
+        db_options::{CacheWrapper, RateLimiterWrapper, WriteBufferManagerWrapper},
         env::{Env, EnvWrapper},
         BlockBasedOptions, BoundColumnFamily, Cache, ColumnFamily, ColumnFamilyDescriptor,
         DBIterator, DBRawIterator, IngestExternalFileOptions, Options, PlainTableFactoryOptions,
-        ReadOptions, Snapshot, SstFileWriter, WriteBatch, WriteBufferManager, WriteOptions, DB,
+        RateLimiter, ReadOptions, Snapshot, SstFileWriter, WriteBatch, WriteBufferManager,
+        WriteOptions, DB,
     };
 
     #[test]
@@ -289,6 +385,8 @@ mod test {
         is_send::<TransactionOptions>();
         is_send::<WriteBufferManager>();
         is_send::<WriteBufferManagerWrapper>();
+        is_send::<RateLimiter>();
+        is_send::<RateLimiterWrapper>();
     }
 
     #[test]
@@ -321,5 +419,7 @@ mod test {
         is_sync::<TransactionOptions>();
         is_sync::<WriteBufferManager>();
         is_sync::<WriteBufferManagerWrapper>();
+        is_sync::<RateLimiter>();
+        is_sync::<RateLimiterWrapper>();
     }
 }
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use libc::{c_char, c_uint, c_void, size_t};
+
+use crate::LogLevel;
+
+/// Receives the lines RocksDB writes to its info LOG.
+///
+/// Set with [`Options::set_logger`] to forward RocksDB's log to the
+/// application's own logging, e.g. the `log` or `tracing` crates. A logger
+/// wrapped in an [`Arc`] is itself a `Logger`, so one instance can be shared
+/// by several DBs.
+///
+/// ```
+/// use rocksdb::{LogLevel, Logger, Options};
+///
+/// struct StderrLogger;
+///
+/// impl Logger for StderrLogger {
+///     fn log(&self, level: LogLevel, message: &str) {
+///         eprintln!("[rocksdb {level:?}] {message}");
+///     }
+/// }
+///
+/// let mut opts = Options::default();
+/// opts.set_logger(LogLevel::Info, StderrLogger);
+/// ```
+///
+/// [`Options::set_logger`]: crate::Options::set_logger
+pub trait Logger: Send + Sync + 'static {
+    /// Called for every log line at or above the level given to
+    /// [`Options::set_logger`](crate::Options::set_logger). May be called
+    /// concurrently from RocksDB's background threads.
+    fn log(&self, level: LogLevel, message: &str);
+}
+
+impl<L: Logger + ?Sized> Logger for Arc<L> {
+    fn log(&self, level: LogLevel, message: &str) {
+        (**self).log(level, message);
+    }
+}
+
+/// Boxed so that the callback state has a thin pointer which stays valid while
+/// the `Arc` holding it is cloned along with the options.
+pub(crate) type LoggerCallback = Box<dyn Logger>;
+
+pub(crate) unsafe extern "C" fn logv_callback(
+    raw_cb: *mut c_void,
+    level: c_uint,
+    msg: *mut c_char,
+    len: size_t,
+) {
+    let cb = unsafe { &*(raw_cb as *const LoggerCallback) };
+    let level = match level {
+        0 => LogLevel::Debug,
+        1 => LogLevel::Info,
+        2 => LogLevel::Warn,
+        3 => LogLevel::Error,
+        4 => LogLevel::Fatal,
+        _ => LogLevel::Header,
+    };
+    let msg = unsafe { std::slice::from_raw_parts(msg as *const u8, len) };
+    cb.log(level, &String::from_utf8_lossy(msg));
+}
@@ -60,6 +60,7 @@
 //! ```
 
 use libc::{self, c_char, c_int, c_void, size_t};
+use std::cell::{Cell, RefCell};
 use std::ffi::CString;
 use std::mem;
 use std::ptr;
@@ -101,6 +102,59 @@ pub unsafe extern "C" fn name_callback<F: MergeFn, PF: MergeFn>(
     cb.name.as_ptr()
 }
 
+/// The unmerged state of a key, as returned by
+/// [`DBCommon::get_merge_operands_opt`](crate::DBCommon::get_merge_operands_opt).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnmergedValue {
+    /// The value the operands apply to, or `None` if the key had no value or
+    /// was deleted before the first operand.
+    pub base: Option<Vec<u8>>,
+    /// The merge operands, oldest first, i.e. in the order they are applied.
+    pub operands: Vec<Vec<u8>>,
+}
+
+/// The inputs of a full merge recorded by [`capture_merge_operands`].
+pub(crate) enum CapturedMerge {
+    Unmerged(UnmergedValue),
+    /// The merge had more operands than allowed, so none were copied.
+    TooManyOperands,
+}
+
+thread_local! {
+    // The most operands to record while a read on this thread is captured.
+    static CAPTURE_MERGE: Cell<Option<usize>> = const { Cell::new(None) };
+    static CAPTURED_MERGE: RefCell<Option<CapturedMerge>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with full merges on the current thread recording their inputs
+/// instead of calling the merge function, and returns the recorded inputs.
+/// Inputs with more than `max_operands` operands are not copied.
+///
+/// This emulates RocksDB's `GetMergeOperands`, which the C API does not
+/// expose. It relies on point reads running the full merge on the calling
+/// thread, which RocksDB does not document, so it only affects the reads
+/// issued by `f` and never background flushes or compactions.
+pub(crate) fn capture_merge_operands<T>(
+    max_operands: usize,
+    f: impl FnOnce() -> T,
+) -> (T, Option<CapturedMerge>) {
+    // Resets the flag even if `f` unwinds, so later reads merge normally.
+    struct CaptureGuard;
+
+    impl Drop for CaptureGuard {
+        fn drop(&mut self) {
+            CAPTURE_MERGE.set(None);
+        }
+    }
+
+    CAPTURED_MERGE.take();
+    CAPTURE_MERGE.set(Some(max_operands));
+    let guard = CaptureGuard;
+    let rv = f();
+    drop(guard);
+    (rv, CAPTURED_MERGE.take())
+}
+
 pub unsafe extern "C" fn full_merge_callback<F: MergeFn, PF: MergeFn>(
     raw_cb: *mut c_void,
     raw_key: *const c_char,
@@ -121,6 +175,20 @@ pub unsafe extern "C" fn full_merge_callback<F: MergeFn, PF: MergeFn>(
     } else {
         Some(unsafe { slice::from_raw_parts(existing_value as *const u8, existing_value_len) })
     };
+    if let Some(max_operands) = CAPTURE_MERGE.get() {
+        let captured = if operands.len() > max_operands {
+            CapturedMerge::TooManyOperands
+        } else {
+            CapturedMerge::Unmerged(UnmergedValue {
+                base: oldval.map(<[u8]>::to_vec),
+                operands: operands.iter().map(<[u8]>::to_vec).collect(),
+            })
+        };
+        CAPTURED_MERGE.set(Some(captured));
+        unsafe { *new_value_length = 0 };
+        unsafe { *success = 1_u8 };
+        return Box::into_raw(Box::<[u8]>::default()) as *mut c_char;
+    }
     (cb.full_merge_fn)(key, oldval, operands).map_or_else(
         || {
             unsafe { *new_value_length = 0 };
@@ -0,0 +1,238 @@
+use crate::{
+    db::{DBAccess, DB},
+    db_iterator::{DBRawIteratorWithThreadMode, Direction, IteratorMode, KVBytes},
+    Error, ReadOptions,
+};
+
+/// [`CoalescingIteratorWithThreadMode`] over a [`DB`]. See it for details
+pub type CoalescingIterator<'a> = CoalescingIteratorWithThreadMode<'a, DB>;
+
+/// [`AttributeGroupIteratorWithThreadMode`] over a [`DB`]. See it for details
+pub type AttributeGroupIterator<'a> = AttributeGroupIteratorWithThreadMode<'a, DB>;
+
+/// The values of a key in each column family of an [`AttributeGroupIteratorWithThreadMode`],
+/// in the order the column families were passed when creating the iterator.
+/// Column families which do not contain the key hold `None`.
+pub type AttributeGroups = Vec<Option<Box<[u8]>>>;
+
+/// A key together with its [`AttributeGroups`].
+pub type KeyAttributeGroups = (Box<[u8]>, AttributeGroups);
+
+/// Iterates several column families at once, merging them by key.
+///
+/// Keys are compared bytewise, so all the column families must use the
+/// default comparator. The underlying iterators are created together from a
+/// consistent view of the database and share the given [`ReadOptions`], so
+/// iterate bounds and snapshots apply to every column family.
+struct MultiCfIterator<'a, D: DBAccess> {
+    // Declared before `_readopts` so that the iterators are dropped first.
+    iters: Vec<DBRawIteratorWithThreadMode<'a, D>>,
+    /// The iterators keep pointers into these options, like
+    /// [`DBRawIteratorWithThreadMode`] does for its own ones.
+    _readopts: ReadOptions,
+    direction: Direction,
+    done: bool,
+}
+
+impl<'a, D: DBAccess> MultiCfIterator<'a, D> {
+    fn new(
+        iters: Vec<DBRawIteratorWithThreadMode<'a, D>>,
+        readopts: ReadOptions,
+        mode: IteratorMode,
+    ) -> Self {
+        let mut rv = Self {
+            iters,
+            _readopts: readopts,
+            direction: Direction::Forward, // blown away by set_mode()
+            done: false,
+        };
+        rv.set_mode(mode);
+        rv
+    }
+
+    fn set_mode(&mut self, mode: IteratorMode) {
+        self.done = false;
+        self.direction = match mode {
+            IteratorMode::Start => {
+                for it in &mut self.iters {
+                    it.seek_to_first();
+                }
+                Direction::Forward
+            }
+            IteratorMode::End => {
+                for it in &mut self.iters {
+                    it.seek_to_last();
+                }
+                Direction::Reverse
+            }
+            IteratorMode::From(key, Direction::Forward) => {
+                for it in &mut self.iters {
+                    it.seek(key);
+                }
+                Direction::Forward
+            }
+            IteratorMode::From(key, Direction::Reverse) => {
+                for it in &mut self.iters {
+                    it.seek_for_prev(key);
+                }
+                Direction::Reverse
+            }
+        };
+    }
+
+    /// Returns the current key together with the value of every column family
+    /// positioned on it, and advances past that key.
+    fn next_entry(&mut self) -> Option<Result<KeyAttributeGroups, Error>> {
+        if self.done {
+            return None;
+        }
+        if let Some(err) = self.iters.iter().find_map(|it| it.status().err()) {
+            self.done = true;
+            return Some(Err(err));
+        }
+
+        let current = self
+            .iters
+            .iter()
+            .filter_map(DBRawIteratorWithThreadMode::key)
+            .reduce(|a, b| match self.direction {
+                Direction::Forward => a.min(b),
+                Direction::Reverse => a.max(b),
+            })
+            .map(Box::<[u8]>::from);
+        let Some(key) = current else {
+            self.done = true;
+            return None;
+        };
+
+        let mut values = Vec::with_capacity(self.iters.len());
+        for it in &mut self.iters {
+            match it.item() {
+                Some((k, v)) if *k == *key => {
+                    values.push(Some(Box::from(v)));
+                    match self.direction {
+                        Direction::Forward => it.next(),
+                        Direction::Reverse => it.prev(),
+                    }
+                }
+                _ => values.push(None),
+            }
+        }
+        Some(Ok((key, values)))
+    }
+}
+
+/// A standard Rust [`Iterator`] over several column families which yields
+/// every key once, created by [`DBCommon::coalescing_iterator_cf`] and
+/// [`DBCommon::coalescing_iterator_cf_opt`].
+///
+/// When a key exists in more than one column family, the value is taken from
+/// the column family that comes last in the list, which shadows the earlier
+/// ones. Keys are compared bytewise, so all the column families must use the
+/// default comparator.
+///
+/// ```
+/// use rocksdb::{IteratorMode, Options, DB};
+///
+/// let tempdir = tempfile::Builder::new()
+///     .prefix("_path_for_rocksdb_storage_coalescing")
+///     .tempdir()
+///     .expect("Failed to create temporary path for the _path_for_rocksdb_storage_coalescing.");
+/// let path = tempdir.path();
+/// {
+///     let mut opts = Options::default();
+///     opts.create_if_missing(true);
+///     opts.create_missing_column_families(true);
+///     let db = DB::open_cf(&opts, path, ["hot", "cold"]).unwrap();
+///     let hot = db.cf_handle("hot").unwrap();
+///     let cold = db.cf_handle("cold").unwrap();
+///     db.put_cf(&cold, b"k1", b"old").unwrap();
+///     db.put_cf(&hot, b"k1", b"new").unwrap();
+///     db.put_cf(&cold, b"k2", b"v2").unwrap();
+///
+///     let iter = db.coalescing_iterator_cf([&cold, &hot], IteratorMode::Start).unwrap();
+///     let items: Vec<_> = iter.map(Result::unwrap).collect();
+///     assert_eq!(items[0], (b"k1".to_vec().into(), b"new".to_vec().into()));
+///     assert_eq!(items[1], (b"k2".to_vec().into(), b"v2".to_vec().into()));
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+///
+/// [`DBCommon::coalescing_iterator_cf`]: crate::DBCommon::coalescing_iterator_cf
+/// [`DBCommon::coalescing_iterator_cf_opt`]: crate::DBCommon::coalescing_iterator_cf_opt
+pub struct CoalescingIteratorWithThreadMode<'a, D: DBAccess> {
+    inner: MultiCfIterator<'a, D>,
+}
+
+impl<'a, D: DBAccess> CoalescingIteratorWithThreadMode<'a, D> {
+    pub(crate) fn new(
+        iters: Vec<DBRawIteratorWithThreadMode<'a, D>>,
+        readopts: ReadOptions,
+        mode: IteratorMode,
+    ) -> Self {
+        Self {
+            inner: MultiCfIterator::new(iters, readopts, mode),
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: IteratorMode) {
+        self.inner.set_mode(mode);
+    }
+}
+
+impl<D: DBAccess> Iterator for CoalescingIteratorWithThreadMode<'_, D> {
+    type Item = Result<KVBytes, Error>;
+
+    fn next(&mut self) -> Option<Result<KVBytes, Error>> {
+        self.inner.next_entry().map(|entry| {
+            entry.map(|(key, values)| {
+                // The last column family holding the key shadows the others.
+                let value = values.into_iter().flatten().next_back().unwrap_or_default();
+                (key, value)
+            })
+        })
+    }
+}
+
+impl<D: DBAccess> std::iter::FusedIterator for CoalescingIteratorWithThreadMode<'_, D> {}
+
+/// A standard Rust [`Iterator`] over several column families which yields
+/// every key once together with its value in each of them, created by
+/// [`DBCommon::attribute_group_iterator_cf`] and
+/// [`DBCommon::attribute_group_iterator_cf_opt`].
+///
+/// Each column family holds one attribute group of a logical row, see
+/// [`AttributeGroups`]. Keys are compared bytewise, so all the column families
+/// must use the default comparator.
+///
+/// [`DBCommon::attribute_group_iterator_cf`]: crate::DBCommon::attribute_group_iterator_cf
+/// [`DBCommon::attribute_group_iterator_cf_opt`]: crate::DBCommon::attribute_group_iterator_cf_opt
+pub struct AttributeGroupIteratorWithThreadMode<'a, D: DBAccess> {
+    inner: MultiCfIterator<'a, D>,
+}
+
+impl<'a, D: DBAccess> AttributeGroupIteratorWithThreadMode<'a, D> {
+    pub(crate) fn new(
+        iters: Vec<DBRawIteratorWithThreadMode<'a, D>>,
+        readopts: ReadOptions,
+        mode: IteratorMode,
+    ) -> Self {
+        Self {
+            inner: MultiCfIterator::new(iters, readopts, mode),
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: IteratorMode) {
+        self.inner.set_mode(mode);
+    }
+}
+
+impl<D: DBAccess> Iterator for AttributeGroupIteratorWithThreadMode<'_, D> {
+    type Item = Result<KeyAttributeGroups, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_entry()
+    }
+}
+
+impl<D: DBAccess> std::iter::FusedIterator for AttributeGroupIteratorWithThreadMode<'_, D> {}
@@ -16,7 +16,11 @@
 use crate::{ffi, ffi_util::to_cpath, Error, Options};
 
 use libc::{self, c_char, size_t};
-use std::{ffi::CString, marker::PhantomData, path::Path};
+use std::{
+    ffi::CString,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
 
 /// SstFileWriter is used to create sst files that can be added to database later
 /// All keys in files generated by SstFileWriter will have sequence number = 0.
@@ -25,6 +29,36 @@ pub struct SstFileWriter<'a> {
     // Options are needed to be alive when calling open(),
     // so let's make sure it doesn't get, dropped for the lifetime of SstFileWriter
     phantom: PhantomData<&'a Options>,
+    // The C API does not report what was written, so it is tracked here and
+    // turned into an `ExternalSstFileInfo` by `finish`. The key buffers are
+    // reused to keep allocations off the write path.
+    file_path: PathBuf,
+    smallest_key: Vec<u8>,
+    largest_key: Vec<u8>,
+    num_entries: u64,
+    num_range_del_entries: u64,
+}
+
+/// Describes an SST file written by [`SstFileWriter`], returned by
+/// [`SstFileWriter::finish`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExternalSstFileInfo {
+    /// Path of the file
+    pub file_path: PathBuf,
+    /// Smallest point key in the file, including its timestamp if any.
+    /// `None` if the file only holds range deletions
+    pub smallest_key: Option<Vec<u8>>,
+    /// Largest point key in the file, including its timestamp if any.
+    /// `None` if the file only holds range deletions
+    pub largest_key: Option<Vec<u8>>,
+    /// Sequence number of all the keys in the file, which is always 0
+    pub sequence_number: u64,
+    /// Size of the file in bytes
+    pub file_size: u64,
+    /// Number of point entries (puts, merges and deletes) in the file
+    pub num_entries: u64,
+    /// Number of range deletions in the file
+    pub num_range_del_entries: u64,
 }
 
 unsafe impl Send for SstFileWriter<'_> {}
@@ -59,6 +93,11 @@ impl<'a> SstFileWriter<'a> {
         Self {
             inner: writer,
             phantom: PhantomData,
+            file_path: PathBuf::new(),
+            smallest_key: Vec::new(),
+            largest_key: Vec::new(),
+            num_entries: 0,
+            num_range_del_entries: 0,
         }
     }
 
@@ -67,12 +106,18 @@ impl<'a> SstFileWriter<'a> {
     }
 
     /// Prepare SstFileWriter to write into file located at "file_path".
-    pub fn open<P: AsRef<Path>>(&'a self, path: P) -> Result<(), Error> {
+    pub fn open<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
         let cpath = to_cpath(&path)?;
-        self.open_raw(&cpath)
+        self.open_raw(&cpath)?;
+        self.file_path = path.as_ref().to_path_buf();
+        self.smallest_key.clear();
+        self.largest_key.clear();
+        self.num_entries = 0;
+        self.num_range_del_entries = 0;
+        Ok(())
     }
 
-    fn open_raw(&'a self, cpath: &CString) -> Result<(), Error> {
+    fn open_raw(&self, cpath: &CString) -> Result<(), Error> {
         unsafe {
             ffi_try!(ffi::rocksdb_sstfilewriter_open(
                 self.inner,
@@ -84,11 +129,34 @@ impl<'a> SstFileWriter<'a> {
     }
 
     /// Finalize writing to sst file and close file.
-    pub fn finish(&mut self) -> Result<(), Error> {
+    ///
+    /// Returns a description of the written file, which can help choosing
+    /// how to ingest it.
+    pub fn finish(&mut self) -> Result<ExternalSstFileInfo, Error> {
         unsafe {
             ffi_try!(ffi::rocksdb_sstfilewriter_finish(self.inner,));
-            Ok(())
         }
+        let has_point_keys = self.num_entries > 0;
+        Ok(ExternalSstFileInfo {
+            file_path: self.file_path.clone(),
+            smallest_key: has_point_keys.then(|| self.smallest_key.clone()),
+            largest_key: has_point_keys.then(|| self.largest_key.clone()),
+            sequence_number: 0,
+            file_size: self.file_size(),
+            num_entries: self.num_entries,
+            num_range_del_entries: self.num_range_del_entries,
+        })
+    }
+
+    fn record_point_key(&mut self, key: &[u8], ts: &[u8]) {
+        if self.num_entries == 0 {
+            self.smallest_key.extend_from_slice(key);
+            self.smallest_key.extend_from_slice(ts);
+        }
+        self.largest_key.clear();
+        self.largest_key.extend_from_slice(key);
+        self.largest_key.extend_from_slice(ts);
+        self.num_entries += 1;
     }
 
     /// returns the current file size
@@ -117,8 +185,9 @@ impl<'a> SstFileWriter<'a> {
                 value.as_ptr() as *const c_char,
                 value.len() as size_t,
             ));
-            Ok(())
         }
+        self.record_point_key(key, &[]);
+        Ok(())
     }
 
     /// Adds a Put key with value to currently opened file
@@ -142,8 +211,9 @@ impl<'a> SstFileWriter<'a> {
                 value.as_ptr() as *const c_char,
                 value.len() as size_t,
             ));
-            Ok(())
         }
+        self.record_point_key(key, ts);
+        Ok(())
     }
 
     /// Adds a Merge key with value to currently opened file
@@ -164,8 +234,9 @@ impl<'a> SstFileWriter<'a> {
                 value.as_ptr() as *const c_char,
                 value.len() as size_t,
             ));
-            Ok(())
         }
+        self.record_point_key(key, &[]);
+        Ok(())
     }
 
     /// Adds a deletion key to currently opened file
@@ -179,8 +250,9 @@ impl<'a> SstFileWriter<'a> {
                 key.as_ptr() as *const c_char,
                 key.len() as size_t,
             ));
-            Ok(())
         }
+        self.record_point_key(key, &[]);
+        Ok(())
     }
 
     /// Adds a deletion key to currently opened file
@@ -200,8 +272,30 @@ impl<'a> SstFileWriter<'a> {
                 ts.as_ptr() as *const c_char,
                 ts.len() as size_t,
             ));
-            Ok(())
         }
+        self.record_point_key(key, ts);
+        Ok(())
+    }
+
+    /// Adds a range deletion tombstone for the keys in `[begin_key, end_key)`
+    /// to currently opened file. It does not delete the point keys of the
+    /// same file.
+    ///
+    /// Unlike point keys, range deletions may be added in any order.
+    pub fn delete_range<K: AsRef<[u8]>>(&mut self, begin_key: K, end_key: K) -> Result<(), Error> {
+        let begin_key = begin_key.as_ref();
+        let end_key = end_key.as_ref();
+        unsafe {
+            ffi_try!(ffi::rocksdb_sstfilewriter_delete_range(
+                self.inner,
+                begin_key.as_ptr() as *const c_char,
+                begin_key.len() as size_t,
+                end_key.as_ptr() as *const c_char,
+                end_key.len() as size_t,
+            ));
+        }
+        self.num_range_del_entries += 1;
+        Ok(())
     }
 }
 
@@ -749,6 +749,34 @@ impl<DB> Transaction<'_, DB> {
         Ok(())
     }
 
+    /// Single delete the key value if it exists and do conflict checking on the key.
+    ///
+    /// See [`single_delete_cf`] for details.
+    ///
+    /// [`single_delete_cf`]: Self::single_delete_cf
+    pub fn single_delete<K: AsRef<[u8]>>(&self, key: K) -> Result<(), Error> {
+        let mut batch = WriteBatchWithTransaction::<true>::default();
+        batch.single_delete(key);
+        self.rebuild_from_writebatch(&batch)
+    }
+
+    /// Single delete the key value in the given column family and do conflict checking.
+    ///
+    /// The key must have been written only once, see
+    /// [`DBCommon::single_delete_opt`](crate::DBCommon::single_delete_opt).
+    /// Errors are reported the same way as in [`delete_cf`].
+    ///
+    /// [`delete_cf`]: Self::delete_cf
+    pub fn single_delete_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+    ) -> Result<(), Error> {
+        let mut batch = WriteBatchWithTransaction::<true>::default();
+        batch.single_delete_cf(cf, key);
+        self.rebuild_from_writebatch(&batch)
+    }
+
     pub fn iterator<'a: 'b, 'b>(
         &'a self,
         mode: IteratorMode,
@@ -29,7 +29,7 @@ use std::ffi::CStr;
 use crate::column_family::ColumnFamilyTtl;
 use crate::{
     column_family::UnboundColumnFamily,
-    db::{convert_values, DBAccess},
+    db::{close_db, convert_values, DBAccess},
     db_options::OptionsMustOutliveDB,
     ffi,
     ffi_util::to_cpath,
@@ -397,6 +397,25 @@ impl<T: ThreadMode> TransactionDB<T> {
         self.path.as_path()
     }
 
+    /// Closes the database and reports any error hit while shutting it down.
+    ///
+    /// Prepared transactions which were not taken with
+    /// [`prepared_transactions`](Self::prepared_transactions) are released
+    /// first, as on drop. See [`DBCommon::close`](crate::DBCommon::close) for
+    /// what closing involves.
+    pub fn close(mut self) -> Result<(), Error> {
+        self.prepared_transactions().clear();
+        // RocksDB requires every column family handle to be released before
+        // the database is closed.
+        self.cfs.drop_all_cfs_internal();
+        unsafe {
+            let base = ffi::rocksdb_transactiondb_get_base_db(self.inner);
+            let result = close_db(base);
+            ffi::rocksdb_transactiondb_close_base_db(base);
+            result
+        }
+    }
+
     /// Creates a transaction with default options.
     pub fn transaction(&self) -> Transaction<Self> {
         self.transaction_opt(&WriteOptions::default(), &TransactionOptions::default())
@@ -832,6 +851,46 @@ impl<T: ThreadMode> TransactionDB<T> {
         Ok(())
     }
 
+    pub fn single_delete<K: AsRef<[u8]>>(&self, key: K) -> Result<(), Error> {
+        self.single_delete_opt(key, &WriteOptions::default())
+    }
+
+    pub fn single_delete_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+    ) -> Result<(), Error> {
+        self.single_delete_cf_opt(cf, key, &WriteOptions::default())
+    }
+
+    /// Single delete the key, which must have been written only once.
+    ///
+    /// See [`DBCommon::single_delete_opt`](crate::DBCommon::single_delete_opt)
+    /// for details.
+    pub fn single_delete_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        // The C API has no single delete for TransactionDB, and writing a
+        // batch would skip locking the key, so do what RocksDB does
+        // internally and run it in its own transaction.
+        let txn = self.transaction_opt(writeopts, &TransactionOptions::default());
+        txn.single_delete(key)?;
+        txn.commit()
+    }
+
+    pub fn single_delete_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        let txn = self.transaction_opt(writeopts, &TransactionOptions::default());
+        txn.single_delete_cf(cf, key)?;
+        txn.commit()
+    }
+
     pub fn iterator<'a: 'b, 'b>(
         &'a self,
         mode: IteratorMode,
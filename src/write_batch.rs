@@ -219,6 +219,9 @@ impl<const TRANSACTION: bool> WriteBatchWithTransaction<TRANSACTION> {
     /// this does _not_ return an `Iterator` but instead will invoke the `put()`
     /// and `delete()` member functions of the provided `WriteBatchIterator`
     /// trait implementation.
+    ///
+    /// Single deletes are not reported, as the RocksDB C API provides no
+    /// callback for them.
     pub fn iterate<T: WriteBatchIterator>(&self, callbacks: &mut T) {
         let state = std::ptr::from_mut::<T>(callbacks) as *mut c_void;
         unsafe {
@@ -239,6 +242,8 @@ impl<const TRANSACTION: bool> WriteBatchWithTransaction<TRANSACTION> {
     /// # Notes
     /// - For operations on the default column family ("default"), the `cf_id` parameter passed to
     ///   the callbacks will be 0
+    /// - Single deletes are not reported, as the RocksDB C API provides no callback for them.
+    ///   A single delete in a non-default column family stops the iteration.
     pub fn iterate_cf<T: WriteBatchIteratorCf>(&self, callbacks: &mut T) {
         let state = std::ptr::from_mut::<T>(callbacks) as *mut c_void;
         unsafe {
@@ -407,6 +412,59 @@ impl<const TRANSACTION: bool> WriteBatchWithTransaction<TRANSACTION> {
         }
     }
 
+    /// Removes the database entry for key, which must have been written only once.
+    ///
+    /// See [`DBCommon::single_delete_opt`](crate::DBCommon::single_delete_opt)
+    /// for the requirements on the key.
+    pub fn single_delete<K: AsRef<[u8]>>(&mut self, key: K) {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_singledelete(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            );
+        }
+    }
+
+    /// Removes the database entry in the specific column family for key,
+    /// which must have been written only once.
+    pub fn single_delete_cf<K: AsRef<[u8]>>(&mut self, cf: &impl AsColumnFamilyRef, key: K) {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_singledelete_cf(
+                self.inner,
+                cf.inner(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            );
+        }
+    }
+
+    /// Removes the database entry in the specific column family with timestamp
+    /// for key, which must have been written only once.
+    pub fn single_delete_cf_with_ts<K: AsRef<[u8]>, S: AsRef<[u8]>>(
+        &mut self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        ts: S,
+    ) {
+        let key = key.as_ref();
+        let ts = ts.as_ref();
+        unsafe {
+            ffi::rocksdb_writebatch_singledelete_cf_with_ts(
+                self.inner,
+                cf.inner(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                ts.as_ptr() as *const c_char,
+                ts.len() as size_t,
+            );
+        }
+    }
+
     // Append a blob of arbitrary size to the records in this batch. The blob will
     // be stored in the transaction log but not in any other file. In particular,
     // it will not be persisted to the SST files. When iterating over this
@@ -0,0 +1,125 @@
+mod util;
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{
+    BulkLoader, IngestExternalFileOptions, IteratorMode, Options, DB, DEFAULT_COLUMN_FAMILY_NAME,
+};
+use util::DBPath;
+
+#[test]
+fn bulk_loader_test() {
+    let path = DBPath::new("_rust_rocksdb_bulk_loader_test");
+    let dir = tempfile::Builder::new()
+        .prefix("_rust_rocksdb_bulk_loader_test")
+        .tempdir()
+        .expect("Failed to create temporary path for the bulk loader.");
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, ["index"]).unwrap();
+    let index = db.cf_handle("index").unwrap();
+    db.put(b"k0000", b"old").unwrap();
+
+    let mut loader = BulkLoader::new(&db, &opts, dir.path()).unwrap();
+    // Column families other than the default one need their options first.
+    assert!(loader.put_cf(&index, b"v", b"k").is_err());
+    loader.set_cf_options(&index, &opts);
+    // Small enough to spill several runs and write several SST files.
+    loader.set_memory_budget(16 << 10);
+    loader.set_target_file_size(8 << 10);
+    for i in (0..1000).rev() {
+        loader.put(format!("k{i:04}"), format!("v{i}")).unwrap();
+        loader
+            .put_cf(&index, format!("v{i}"), format!("k{i:04}"))
+            .unwrap();
+    }
+    // Overwrites, including ones whose earlier value is already spilled.
+    for i in (0..1000).step_by(100) {
+        loader.put(format!("k{i:04}"), "new").unwrap();
+    }
+    loader
+        .finish(&IngestExternalFileOptions::default())
+        .unwrap();
+
+    let items: Vec<_> = db
+        .iterator(IteratorMode::Start)
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(items.len(), 1000);
+    for (i, (key, value)) in items.iter().enumerate() {
+        assert_eq!(**key, *format!("k{i:04}").as_bytes());
+        if i % 100 == 0 {
+            assert_eq!(**value, *b"new");
+        } else {
+            assert_eq!(**value, *format!("v{i}").as_bytes());
+        }
+    }
+    assert_eq!(db.get_cf(&index, b"v42").unwrap().unwrap(), b"k0042");
+    assert_eq!(db.iterator_cf(&index, IteratorMode::Start).count(), 1000);
+
+    let live_files = db.live_files().unwrap();
+    assert!(
+        live_files
+            .iter()
+            .filter(|f| f.column_family_name == "default")
+            .count()
+            > 1
+    );
+
+    // Temporary files are removed once the load is done.
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn bulk_loader_many_runs_test() {
+    let path = DBPath::new("_rust_rocksdb_bulk_loader_many_runs_test");
+    let dir = tempfile::Builder::new()
+        .prefix("_rust_rocksdb_bulk_loader_many_runs_test")
+        .tempdir()
+        .expect("Failed to create temporary path for the bulk loader.");
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open_cf(&opts, &path, [DEFAULT_COLUMN_FAMILY_NAME]).unwrap();
+    let default = db.cf_handle(DEFAULT_COLUMN_FAMILY_NAME).unwrap();
+
+    // Two loaders sharing a directory keep their files apart.
+    let mut other = BulkLoader::new(&db, &opts, dir.path()).unwrap();
+    other.put(b"other", b"value").unwrap();
+    other.set_memory_budget(0);
+
+    let mut loader = BulkLoader::new(&db, &opts, dir.path()).unwrap();
+    // Every pair is spilled as its own run, so the runs are merged in passes.
+    loader.set_memory_budget(0);
+    for i in (0..500).rev() {
+        loader.put(format!("k{i:04}"), format!("v{i}")).unwrap();
+    }
+    // The default column family is the same load, whichever way it is named.
+    for i in (0..500).step_by(50) {
+        loader.put_cf(&default, format!("k{i:04}"), "new").unwrap();
+        loader.put(format!("k{i:04}"), "newest").unwrap();
+    }
+    loader
+        .finish(&IngestExternalFileOptions::default())
+        .unwrap();
+
+    let items: Vec<_> = db
+        .iterator(IteratorMode::Start)
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(items.len(), 500);
+    for (i, (key, value)) in items.iter().enumerate() {
+        assert_eq!(**key, *format!("k{i:04}").as_bytes());
+        if i % 50 == 0 {
+            assert_eq!(**value, *b"newest");
+        } else {
+            assert_eq!(**value, *format!("v{i}").as_bytes());
+        }
+    }
+
+    other.finish(&IngestExternalFileOptions::default()).unwrap();
+    assert_eq!(db.get(b"other").unwrap().unwrap(), b"value");
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
@@ -24,10 +24,10 @@ use rocksdb::statistics::{Histogram, StatsLevel, Ticker};
 use rocksdb::{
     perf::get_memory_usage_stats, BlockBasedOptions, BottommostLevelCompaction, Cache,
     ColumnFamilyDescriptor, ColumnFamilyTtl, CompactOptions, CuckooTableOptions, DBAccess,
-    DBCompactionStyle, DBWithThreadMode, Env, Error, ErrorKind, FifoCompactOptions, IteratorMode,
-    MultiThreaded, Options, PerfContext, PerfMetric, ReadOptions, SingleThreaded, SliceTransform,
-    Snapshot, UniversalCompactOptions, UniversalCompactionStopStyle, WaitForCompactOptions,
-    WriteBatch, DB, DEFAULT_COLUMN_FAMILY_NAME,
+    DBCompactionStyle, DBWithThreadMode, Env, Error, ErrorKind, ErrorSubCode, FifoCompactOptions,
+    IteratorMode, MultiThreaded, Options, PerfContext, PerfMetric, ReadOptions, SingleThreaded,
+    SliceTransform, Snapshot, UniversalCompactOptions, UniversalCompactionStopStyle,
+    WaitForCompactOptions, WriteBatch, WriteOptions, DB, DEFAULT_COLUMN_FAMILY_NAME,
 };
 use util::{assert_iter, pair, DBPath, U64Comparator, U64Timestamp};
 
@@ -77,6 +77,8 @@ fn errors_do_stuff() {
         Err(s) => {
             let message = s.to_string();
             assert_eq!(s.kind(), ErrorKind::IOError);
+            assert_eq!(s.subcode(), ErrorSubCode::NoSubCode);
+            assert!(!s.is_retryable());
             assert!(message.contains("_rust_rocksdb_error"));
             assert!(message.contains("/LOCK:"));
         }
@@ -434,6 +436,27 @@ fn set_column_family_metadata_test() {
         let cf2_metadata = db.get_column_family_metadata_cf(&cf2);
         assert_eq!(cf2_metadata.size > default_cf_metadata.size, true);
         assert_eq!(cf2_metadata.file_count, 1);
+
+        let level0 = &cf2_metadata.levels[0];
+        assert_eq!(level0.level, 0);
+        assert_eq!(level0.size, cf2_metadata.size);
+        assert_eq!(level0.files.len(), 1);
+        let file = &level0.files[0];
+        assert_eq!(file.size, cf2_metadata.size);
+        assert_eq!(file.smallest_key, b"key1");
+        assert_eq!(file.largest_key, b"key3");
+        assert_eq!(
+            file.relative_filename,
+            format!("{:06}.sst", file.file_number)
+        );
+        let live_file = db
+            .live_files()
+            .unwrap()
+            .into_iter()
+            .find(|f| f.column_family_name == "cf2")
+            .unwrap();
+        assert_eq!(live_file.name, format!("/{}", file.relative_filename));
+        assert!(cf2_metadata.levels[1..].iter().all(|l| l.files.is_empty()));
     }
 }
 
@@ -1361,6 +1384,56 @@ fn delete_range_test() {
     }
 }
 
+#[test]
+fn single_delete_test() {
+    let path = DBPath::new("_rust_rocksdb_single_delete_test");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+
+        let db = DB::open_cf(&opts, &path, ["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+
+        db.put(b"k1", b"v1").unwrap();
+        db.put_cf(&cf1, b"k2", b"v2").unwrap();
+        // keep the values apart from their tombstones until compaction
+        db.flush().unwrap();
+        db.flush_cf(&cf1).unwrap();
+        db.single_delete(b"k1").unwrap();
+        db.single_delete_cf(&cf1, b"k2").unwrap();
+        assert!(db.get(b"k1").unwrap().is_none());
+        assert!(db.get_cf(&cf1, b"k2").unwrap().is_none());
+
+        db.put(b"k3", b"v3").unwrap();
+        db.put_cf(&cf1, b"k4", b"v4").unwrap();
+        let mut batch = WriteBatch::default();
+        batch.single_delete(b"k3");
+        batch.single_delete_cf(&cf1, b"k4");
+        assert_eq!(batch.len(), 2);
+        db.write(batch).unwrap();
+        assert!(db.get(b"k3").unwrap().is_none());
+        assert!(db.get_cf(&cf1, b"k4").unwrap().is_none());
+
+        let num_deletions = |db: &DB| {
+            let live_files = db.live_files().unwrap();
+            live_files.iter().map(|f| f.num_deletions).sum::<u64>()
+        };
+        db.flush().unwrap();
+        db.flush_cf(&cf1).unwrap();
+        assert_eq!(num_deletions(&db), 2);
+
+        // the tombstones and the values they cover are dropped by compaction, which
+        // is forced since the files would otherwise only be moved to the last level
+        let mut compact_opts = CompactOptions::default();
+        compact_opts.set_bottommost_level_compaction(BottommostLevelCompaction::Force);
+        db.compact_range_opt(None::<&[u8]>, None::<&[u8]>, &compact_opts);
+        db.compact_range_cf_opt(&cf1, None::<&[u8]>, None::<&[u8]>, &compact_opts);
+        assert_eq!(num_deletions(&db), 0);
+        assert!(db.live_files().unwrap().iter().all(|f| f.num_entries == 0));
+    }
+}
+
 #[test]
 fn multi_get() {
     let path = DBPath::new("_rust_rocksdb_multi_get");
@@ -1772,3 +1845,66 @@ fn test_enable_and_disable_file_deletions() {
         let _ = DB::destroy(&Options::default(), &path);
     }
 }
+
+#[test]
+fn test_close() {
+    let path = DBPath::new("_rust_rocksdb_close");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    {
+        // Writes without the WAL are only kept if closing flushes them.
+        let mut write_opts = WriteOptions::default();
+        write_opts.disable_wal(true);
+        let db = DB::open_cf(&opts, &path, ["cf1"]).unwrap();
+        db.put_opt(b"k0", b"v0", &write_opts).unwrap();
+        db.put_cf_opt(&db.cf_handle("cf1").unwrap(), b"k1", b"v1", &write_opts)
+            .unwrap();
+        db.close().unwrap();
+    }
+
+    let db: DBWithThreadMode<MultiThreaded> =
+        DBWithThreadMode::open_cf(&opts, &path, ["cf1"]).unwrap();
+    assert_eq!(db.get(b"k0").unwrap().unwrap(), b"v0");
+    assert_eq!(
+        db.get_cf(&db.cf_handle("cf1").unwrap(), b"k1")
+            .unwrap()
+            .unwrap(),
+        b"v1"
+    );
+    db.close().unwrap();
+
+    // Syncing the WAL on close is skipped by read-only instances.
+    let db = DB::open_cf_for_read_only(&opts, &path, ["cf1"], false).unwrap();
+    db.close().unwrap();
+}
+
+#[test]
+fn test_disable_and_enable_manual_compaction() {
+    let path = DBPath::new("_rust_rocksdb_disable_and_enable_manual_compaction");
+    // Obsolete files may linger on disk for a while, so only live ones count.
+    let get_sst_files = |db: &DB| db.live_files().unwrap().len();
+    // Forced, so that files are rewritten rather than moved to the last level.
+    let mut compact_opts = CompactOptions::default();
+    compact_opts.set_bottommost_level_compaction(BottommostLevelCompaction::Force);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_disable_auto_compactions(true);
+    let db = DB::open(&opts, &path).unwrap();
+    // Overlapping files, so that compacting them merges them into one.
+    for i in 0..2 {
+        db.put(b"k0", format!("v{i}")).unwrap();
+        db.put(b"k1", format!("v{i}")).unwrap();
+        db.flush().unwrap();
+    }
+    assert_eq!(get_sst_files(&db), 2);
+
+    db.disable_manual_compaction();
+    db.compact_range_opt(None::<&[u8]>, None::<&[u8]>, &compact_opts);
+    assert_eq!(get_sst_files(&db), 2);
+
+    db.enable_manual_compaction();
+    db.compact_range_opt(None::<&[u8]>, None::<&[u8]>, &compact_opts);
+    assert_eq!(get_sst_files(&db), 1);
+}
@@ -0,0 +1,250 @@
+mod util;
+
+use std::sync::{Arc, Mutex};
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{
+    event_listener::{
+        BackgroundError, BackgroundErrorReason, CompactionJobInfo, ExternalFileIngestionInfo,
+        FlushJobInfo, MemTableInfo, SubcompactionJobInfo, WriteStallCondition, WriteStallInfo,
+    },
+    BottommostLevelCompaction, CompactOptions, EventListener, MergeOperands, Options,
+    SstFileWriter, WaitForCompactOptions, DB,
+};
+use util::DBPath;
+
+#[derive(Clone, Default)]
+struct RecordingListener {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl RecordingListener {
+    fn record(&self, event: String) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+}
+
+impl EventListener for RecordingListener {
+    fn on_flush_begin(&self, info: &FlushJobInfo) {
+        self.record(format!("flush_begin {}", info.cf_name()));
+    }
+
+    fn on_flush_completed(&self, info: &FlushJobInfo) {
+        self.record(format!(
+            "flush_completed {} {} {}",
+            info.cf_name(),
+            info.smallest_seqno(),
+            info.largest_seqno()
+        ));
+    }
+
+    fn on_compaction_begin(&self, info: &CompactionJobInfo) {
+        self.record(format!(
+            "compaction_begin {} {}",
+            info.cf_name(),
+            info.input_files().len()
+        ));
+    }
+
+    fn on_compaction_completed(&self, info: &CompactionJobInfo) {
+        self.record(format!(
+            "compaction_completed {} {:?} {} {} {}",
+            info.cf_name(),
+            info.status(),
+            info.input_records(),
+            info.output_records(),
+            info.output_files().len()
+        ));
+    }
+
+    fn on_external_file_ingested(&self, info: &ExternalFileIngestionInfo) {
+        self.record(format!(
+            "ingested {} {}",
+            info.cf_name(),
+            info.internal_file_path().ends_with(".sst")
+        ));
+    }
+
+    fn on_memtable_sealed(&self, info: &MemTableInfo) {
+        self.record(format!(
+            "memtable_sealed {} {}",
+            info.cf_name(),
+            info.num_entries()
+        ));
+    }
+}
+
+#[test]
+fn event_listener_test() {
+    let path = DBPath::new("_rust_rocksdb_event_listener_test");
+    let listener = RecordingListener::default();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_disable_auto_compactions(true);
+    opts.add_event_listener(listener.clone());
+
+    let db = DB::open(&opts, &path).unwrap();
+    let wait_opts = WaitForCompactOptions::default();
+
+    db.put(b"k1", b"v1").unwrap();
+    db.put(b"k2", b"v2").unwrap();
+    db.flush().unwrap();
+    db.wait_for_compact(&wait_opts).unwrap();
+    assert_eq!(
+        listener.take(),
+        [
+            "memtable_sealed default 2",
+            "flush_begin default",
+            "flush_completed default 1 2",
+        ]
+    );
+
+    db.put(b"k1", b"v3").unwrap();
+    db.put(b"k2", b"v4").unwrap();
+    db.flush().unwrap();
+    db.compact_range(None::<&[u8]>, None::<&[u8]>);
+    db.wait_for_compact(&wait_opts).unwrap();
+    let events = listener.take();
+    assert_eq!(
+        &events[events.len() - 2..],
+        [
+            "compaction_begin default 2",
+            "compaction_completed default Ok(()) 4 2 1"
+        ]
+    );
+
+    let dir = tempfile::Builder::new()
+        .prefix("_rust_rocksdb_event_listener_test")
+        .tempdir()
+        .expect("Failed to create temporary path for file writer.");
+    let writer_path = dir.path().join("filewriter");
+    let mut writer = SstFileWriter::create(&opts);
+    writer.open(&writer_path).unwrap();
+    writer.put(b"k5", b"v5").unwrap();
+    writer.finish().unwrap();
+    db.ingest_external_file(vec![&writer_path]).unwrap();
+    assert_eq!(listener.take(), ["ingested default true"]);
+}
+
+#[derive(Clone, Default)]
+struct BackgroundErrorListener {
+    suppress: bool,
+    reasons: Arc<Mutex<Vec<BackgroundErrorReason>>>,
+}
+
+impl EventListener for BackgroundErrorListener {
+    fn on_background_error(&self, reason: BackgroundErrorReason, error: &mut BackgroundError) {
+        self.reasons.lock().unwrap().push(reason);
+        if self.suppress {
+            error.suppress();
+        }
+    }
+}
+
+fn failing_merge(_key: &[u8], _val: Option<&[u8]>, _operands: &MergeOperands) -> Option<Vec<u8>> {
+    None
+}
+
+#[test]
+fn background_error_test() {
+    for suppress in [false, true] {
+        let path = DBPath::new(&format!("_rust_rocksdb_background_error_test_{suppress}"));
+        let listener = BackgroundErrorListener {
+            suppress,
+            ..BackgroundErrorListener::default()
+        };
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_disable_auto_compactions(true);
+        opts.set_merge_operator_associative("failing merge", failing_merge);
+        opts.add_event_listener(listener.clone());
+
+        let db = DB::open(&opts, &path).unwrap();
+        // Each flush holds a single entry, so only the compaction merges.
+        db.put(b"k1", b"v1").unwrap();
+        db.flush().unwrap();
+        db.merge(b"k1", b"v2").unwrap();
+        db.flush().unwrap();
+        let mut compact_opts = CompactOptions::default();
+        compact_opts.set_bottommost_level_compaction(BottommostLevelCompaction::Force);
+        db.compact_range_opt(None::<&[u8]>, None::<&[u8]>, &compact_opts);
+        assert_eq!(
+            *listener.reasons.lock().unwrap(),
+            [BackgroundErrorReason::Compaction]
+        );
+
+        // Unless the error is suppressed, the DB stops accepting writes.
+        let written = db.put(b"k2", b"v2");
+        assert_eq!(written.is_ok(), suppress);
+    }
+}
+
+#[derive(Clone, Default)]
+struct StallListener {
+    stalls: Arc<Mutex<Vec<(WriteStallCondition, WriteStallCondition)>>>,
+    subcompactions: Arc<Mutex<Vec<String>>>,
+}
+
+impl EventListener for StallListener {
+    fn on_stall_conditions_changed(&self, info: &WriteStallInfo) {
+        self.stalls.lock().unwrap().push((info.prev(), info.cur()));
+    }
+
+    fn on_subcompaction_begin(&self, info: &SubcompactionJobInfo) {
+        self.subcompactions
+            .lock()
+            .unwrap()
+            .push(format!("begin {}", info.cf_name()));
+    }
+
+    fn on_subcompaction_completed(&self, info: &SubcompactionJobInfo) {
+        self.subcompactions.lock().unwrap().push(format!(
+            "completed {} {:?}",
+            info.cf_name(),
+            info.status()
+        ));
+    }
+}
+
+#[test]
+fn stall_and_subcompaction_test() {
+    let path = DBPath::new("_rust_rocksdb_stall_and_subcompaction_test");
+    let listener = StallListener::default();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    // Two L0 files slow writes down until they are compacted.
+    opts.set_level_zero_file_num_compaction_trigger(2);
+    opts.set_level_zero_slowdown_writes_trigger(2);
+    opts.set_level_zero_stop_writes_trigger(100);
+    opts.add_event_listener(listener.clone());
+
+    let db = DB::open(&opts, &path).unwrap();
+    for i in 0..2 {
+        db.put(b"k1", format!("v{i}")).unwrap();
+        db.flush().unwrap();
+    }
+    db.wait_for_compact(&WaitForCompactOptions::default())
+        .unwrap();
+
+    // The flush and the compaction report their changes from their own
+    // threads, so the order they are recorded in may vary.
+    let stalls = listener.stalls.lock().unwrap().clone();
+    assert_eq!(stalls.len(), 2);
+    assert!(stalls.contains(&(WriteStallCondition::Normal, WriteStallCondition::Delayed)));
+    assert!(stalls.contains(&(WriteStallCondition::Delayed, WriteStallCondition::Normal)));
+
+    // A forced compaction rewrites the files in a single subcompaction.
+    listener.subcompactions.lock().unwrap().clear();
+    let mut compact_opts = CompactOptions::default();
+    compact_opts.set_bottommost_level_compaction(BottommostLevelCompaction::Force);
+    db.compact_range_opt(None::<&[u8]>, None::<&[u8]>, &compact_opts);
+    assert_eq!(
+        *listener.subcompactions.lock().unwrap(),
+        ["begin default", "completed default Ok(())"]
+    );
+}
@@ -16,7 +16,7 @@ mod util;
 
 use pretty_assertions::assert_eq;
 
-use rocksdb::{Direction, IteratorMode, MemtableFactory, Options, DB};
+use rocksdb::{Direction, IteratorMode, MemtableFactory, Options, ReadOptions, DB};
 use util::{assert_iter, assert_iter_reversed, pair, DBPath};
 
 #[test]
@@ -379,3 +379,104 @@ fn test_iter_range() {
         check(&db, b"b0", prefix(b"\xff"), 0..0);
     }
 }
+
+#[test]
+fn test_coalescing_iterator() {
+    let path = DBPath::new("_rust_rocksdb_coalescing_iterator_test");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, ["cf1", "cf2"]).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+    let cf2 = db.cf_handle("cf2").unwrap();
+
+    db.put_cf(&cf1, b"k1", b"v1").unwrap();
+    db.put_cf(&cf1, b"k2", b"old").unwrap();
+    db.put_cf(&cf2, b"k2", b"new").unwrap();
+    db.put_cf(&cf2, b"k3", b"v3").unwrap();
+    db.put_cf(&cf1, b"k4", b"v4").unwrap();
+
+    let expected = [
+        pair(b"k1", b"v1"),
+        pair(b"k2", b"new"),
+        pair(b"k3", b"v3"),
+        pair(b"k4", b"v4"),
+    ];
+    let iter = db
+        .coalescing_iterator_cf([&cf1, &cf2], IteratorMode::Start)
+        .unwrap();
+    assert_iter(iter, &expected);
+    let iter = db
+        .coalescing_iterator_cf([&cf1, &cf2], IteratorMode::End)
+        .unwrap();
+    assert_iter_reversed(iter, &expected);
+
+    // The later column family shadows the earlier ones.
+    let iter = db
+        .coalescing_iterator_cf([&cf2, &cf1], IteratorMode::From(b"k2", Direction::Forward))
+        .unwrap();
+    assert_iter(
+        iter,
+        &[pair(b"k2", b"old"), pair(b"k3", b"v3"), pair(b"k4", b"v4")],
+    );
+    let mut iter = db
+        .coalescing_iterator_cf([&cf1, &cf2], IteratorMode::From(b"k3", Direction::Reverse))
+        .unwrap();
+    assert_eq!(iter.next().unwrap().unwrap(), pair(b"k3", b"v3"));
+    iter.set_mode(IteratorMode::Start);
+    assert_eq!(iter.next().unwrap().unwrap(), pair(b"k1", b"v1"));
+
+    // Bounds and snapshots apply to every column family.
+    let snapshot = db.snapshot();
+    db.put_cf(&cf2, b"k1", b"after snapshot").unwrap();
+    let mut readopts = ReadOptions::default();
+    readopts.set_snapshot(&snapshot);
+    readopts.set_iterate_range(b"k1".as_slice()..b"k4".as_slice());
+    let iter = db
+        .coalescing_iterator_cf_opt([&cf1, &cf2], readopts, IteratorMode::Start)
+        .unwrap();
+    assert_iter(iter, &expected[..3]);
+
+    let empty: [&rocksdb::ColumnFamily; 0] = [];
+    assert!(db
+        .coalescing_iterator_cf(empty, IteratorMode::Start)
+        .is_err());
+}
+
+#[test]
+fn test_attribute_group_iterator() {
+    let path = DBPath::new("_rust_rocksdb_attribute_group_iterator_test");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, ["cf1", "cf2"]).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+    let cf2 = db.cf_handle("cf2").unwrap();
+
+    db.put_cf(&cf1, b"k1", b"a1").unwrap();
+    db.put_cf(&cf1, b"k2", b"a2").unwrap();
+    db.put_cf(&cf2, b"k2", b"b2").unwrap();
+    db.put_cf(&cf2, b"k3", b"b3").unwrap();
+
+    let some = |v: &[u8]| Some(Box::<[u8]>::from(v));
+    let expected = vec![
+        (Box::<[u8]>::from(&b"k1"[..]), vec![some(b"a1"), None]),
+        (Box::from(&b"k2"[..]), vec![some(b"a2"), some(b"b2")]),
+        (Box::from(&b"k3"[..]), vec![None, some(b"b3")]),
+    ];
+
+    let got = db
+        .attribute_group_iterator_cf([&cf1, &cf2], IteratorMode::Start)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(got, expected);
+
+    let mut got = db
+        .attribute_group_iterator_cf([&cf1, &cf2], IteratorMode::End)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    got.reverse();
+    assert_eq!(got, expected);
+}
@@ -16,7 +16,10 @@ mod util;
 
 use bincode::{Decode, Encode};
 use pretty_assertions::assert_eq;
-use rocksdb::{merge_operator::MergeFn, DBCompactionStyle, MergeOperands, Options, DB};
+use rocksdb::{
+    merge_operator::MergeFn, DBCompactionStyle, ErrorKind, ErrorSubCode, MergeOperands, Options,
+    ReadOptions, DB, DEFAULT_COLUMN_FAMILY_NAME,
+};
 use util::DBPath;
 
 const BINCODE_CONFIG: bincode::config::Configuration = bincode::config::standard();
@@ -78,6 +81,103 @@ fn merge_test() {
     assert!(db.get(b"k1").unwrap().is_none());
 }
 
+#[test]
+fn get_merge_operands_test() {
+    let db_path = DBPath::new("_rust_rocksdb_get_merge_operands_test");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_merge_operator_associative("test operator", test_provided_merge);
+
+    let db = DB::open(&opts, &db_path).unwrap();
+    db.put(b"k1", b"a").unwrap();
+    db.merge(b"k1", b"b").unwrap();
+    db.merge(b"k1", b"c").unwrap();
+    let snapshot = db.snapshot();
+    db.merge(b"k1", b"d").unwrap();
+    db.merge(b"k2", b"x").unwrap();
+    db.put(b"k3", b"plain").unwrap();
+
+    let unmerged = db.get_merge_operands(b"k1", 10).unwrap().unwrap();
+    assert_eq!(unmerged.base.as_deref(), Some(&b"a"[..]));
+    assert_eq!(
+        unmerged.operands,
+        vec![b"b".to_vec(), b"c".to_vec(), b"d".to_vec()]
+    );
+    // The merge function still runs for regular reads.
+    assert_eq!(db.get(b"k1").unwrap().unwrap(), b"abcd");
+
+    let mut readopts = ReadOptions::default();
+    readopts.set_snapshot(&snapshot);
+    let unmerged = db
+        .get_merge_operands_opt(b"k1", 10, &readopts)
+        .unwrap()
+        .unwrap();
+    assert_eq!(unmerged.operands, vec![b"b".to_vec(), b"c".to_vec()]);
+
+    let err = db.get_merge_operands(b"k1", 2).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Incomplete);
+    assert_eq!(
+        err.subcode(),
+        ErrorSubCode::MergeOperandsInsufficientCapacity
+    );
+
+    let unmerged = db.get_merge_operands(b"k2", 10).unwrap().unwrap();
+    assert_eq!(unmerged.base, None);
+    assert_eq!(unmerged.operands, vec![b"x".to_vec()]);
+
+    let unmerged = db.get_merge_operands(b"k3", 0).unwrap().unwrap();
+    assert_eq!(unmerged.base.as_deref(), Some(&b"plain"[..]));
+    assert!(unmerged.operands.is_empty());
+
+    assert!(db.get_merge_operands(b"k4", 10).unwrap().is_none());
+}
+
+#[test]
+fn get_merge_operands_cf_test() {
+    let db_path = DBPath::new("_rust_rocksdb_get_merge_operands_cf_test");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    opts.set_merge_operator_associative("test operator", test_provided_merge);
+
+    let db = DB::open_cf_with_opts(
+        &opts,
+        &db_path,
+        [
+            (DEFAULT_COLUMN_FAMILY_NAME, opts.clone()),
+            ("cf1", opts.clone()),
+        ],
+    )
+    .unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+    db.put_cf(&cf1, b"k1", b"a").unwrap();
+    db.merge_cf(&cf1, b"k1", b"b").unwrap();
+    let snapshot = db.snapshot();
+    db.merge_cf(&cf1, b"k1", b"c").unwrap();
+    db.merge(b"k1", b"x").unwrap();
+
+    let unmerged = db.get_merge_operands_cf(&cf1, b"k1", 10).unwrap().unwrap();
+    assert_eq!(unmerged.base.as_deref(), Some(&b"a"[..]));
+    assert_eq!(unmerged.operands, vec![b"b".to_vec(), b"c".to_vec()]);
+
+    let mut readopts = ReadOptions::default();
+    readopts.set_snapshot(&snapshot);
+    let unmerged = db
+        .get_merge_operands_cf_opt(&cf1, b"k1", 10, &readopts)
+        .unwrap()
+        .unwrap();
+    assert_eq!(unmerged.operands, vec![b"b".to_vec()]);
+
+    let err = db.get_merge_operands_cf(&cf1, b"k1", 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Incomplete);
+    assert!(db.get_merge_operands_cf(&cf1, b"k2", 10).unwrap().is_none());
+
+    // The default column family keeps its own operands.
+    let unmerged = db.get_merge_operands(b"k1", 10).unwrap().unwrap();
+    assert_eq!(unmerged.base, None);
+    assert_eq!(unmerged.operands, vec![b"x".to_vec()]);
+}
+
 #[derive(Decode, Encode, Copy, Clone, Debug, Default)]
 struct ValueCounts {
     num_a: u32,
@@ -609,3 +609,20 @@ fn delete_range_test() {
         assert!(db.get_cf(&cf1, b"k3").unwrap().is_none());
     }
 }
+
+#[test]
+fn close() {
+    let path = DBPath::new("_rust_rocksdb_optimistic_transaction_db_close");
+    {
+        let mut write_opts = WriteOptions::default();
+        write_opts.disable_wal(true);
+        let db: OptimisticTransactionDB = OptimisticTransactionDB::open_default(&path).unwrap();
+        db.put_opt(b"k1", b"v1", &write_opts).unwrap();
+        db.close().unwrap();
+    }
+    {
+        let db: OptimisticTransactionDB = OptimisticTransactionDB::open_default(&path).unwrap();
+        assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+        db.close().unwrap();
+    }
+}
@@ -14,12 +14,17 @@
 
 mod util;
 
-use std::{fs, io::Read as _};
+use std::{
+    fs,
+    io::Read as _,
+    sync::{Arc, Mutex},
+};
 
 use rocksdb::checkpoint::Checkpoint;
+use rocksdb::statistics::Ticker;
 use rocksdb::{
     BlockBasedOptions, BlockBasedTablePinningTier, Cache, DBCompressionType, DataBlockIndexType,
-    Env, LruCacheOptions, Options, ReadOptions, DB,
+    Env, LogLevel, Logger, LruCacheOptions, Options, RateLimiter, RateLimiterMode, ReadOptions, DB,
 };
 use util::DBPath;
 
@@ -382,6 +387,69 @@ fn test_set_ratelimiter() {
     }
 }
 
+#[test]
+fn test_set_shared_ratelimiter() {
+    let path1 = DBPath::new("_set_shared_ratelimiter1");
+    let path2 = DBPath::new("_set_shared_ratelimiter2");
+    // 1KB per refill, so flushing each DB has to wait on the limiter.
+    let limiter = RateLimiter::new(1024000, 1000, 1, RateLimiterMode::AllIo, false);
+
+    let mut opts1 = Options::default();
+    opts1.create_if_missing(true);
+    opts1.enable_statistics();
+    opts1.set_compression_type(DBCompressionType::None);
+    opts1.set_shared_ratelimiter(&limiter);
+    let mut opts2 = opts1.clone();
+    opts2.enable_statistics();
+    drop(limiter);
+
+    let db1 = DB::open(&opts1, &path1).unwrap();
+    let db2 = DB::open(&opts2, &path2).unwrap();
+    for i in 0..64u32 {
+        db1.put(i.to_be_bytes(), [b'a'; 1024]).unwrap();
+        db2.put(i.to_be_bytes(), [b'b'; 1024]).unwrap();
+    }
+    db1.flush().unwrap();
+    db2.flush().unwrap();
+    assert!(opts1.get_ticker_count(Ticker::NumberRateLimiterDrains) > 0);
+    assert!(opts2.get_ticker_count(Ticker::NumberRateLimiterDrains) > 0);
+    assert_eq!(
+        &*db1.get(0u32.to_be_bytes()).unwrap().unwrap(),
+        [b'a'; 1024]
+    );
+    assert_eq!(
+        &*db2.get(0u32.to_be_bytes()).unwrap().unwrap(),
+        [b'b'; 1024]
+    );
+}
+
+#[test]
+fn test_set_logger() {
+    struct CollectingLogger(Mutex<Vec<(LogLevel, String)>>);
+
+    impl Logger for CollectingLogger {
+        fn log(&self, level: LogLevel, message: &str) {
+            self.0.lock().unwrap().push((level, message.to_owned()));
+        }
+    }
+
+    let path = DBPath::new("_set_logger");
+    let logger = Arc::new(CollectingLogger(Mutex::new(Vec::new())));
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_logger(LogLevel::Info, logger.clone());
+        let db = DB::open(&opts, &path).unwrap();
+        db.put(b"k1", b"a").unwrap();
+        db.flush().unwrap();
+    }
+
+    let lines = logger.0.lock().unwrap();
+    assert!(lines.iter().any(|(_, msg)| msg.contains("DB pointer")));
+    assert!(lines.iter().all(|(level, _)| *level != LogLevel::Debug));
+    assert!(!(&path).as_ref().join("LOG").exists());
+}
+
 #[test]
 fn test_set_blob_cache() {
     let path = DBPath::new("_set_blob_cache");
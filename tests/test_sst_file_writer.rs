@@ -16,7 +16,10 @@ mod util;
 
 use pretty_assertions::assert_eq;
 
-use rocksdb::{Error, Options, ReadOptions, SstFileWriter, DB};
+use rocksdb::{
+    Error, ErrorKind, ExternalSstFileInfo, IngestExternalFileOptions, Options, ReadOptions,
+    SstFileWriter, DB,
+};
 use util::{DBPath, U64Comparator, U64Timestamp};
 
 #[test]
@@ -122,3 +125,84 @@ fn sst_file_writer_with_ts_works() {
         assert!(db.get_opt(b"k3", &opts).unwrap().is_none());
     }
 }
+
+#[test]
+fn sst_file_writer_delete_range_and_info() {
+    let db_path = DBPath::new("_rust_rocksdb_sstfilewritertest_delete_range");
+    let dir = tempfile::Builder::new()
+        .prefix("_rust_rocksdb_sstfilewritertest_delete_range")
+        .tempdir()
+        .expect("Failed to create temporary path for file writer.");
+    let writer_path = dir.path().join("filewriter");
+    let info = {
+        let opts = Options::default();
+        let mut writer = SstFileWriter::create(&opts);
+        writer.open(&writer_path).unwrap();
+        writer.put(b"k1", b"v1").unwrap();
+        writer.merge(b"k2", b"v2").unwrap();
+        writer.delete(b"k4").unwrap();
+        writer.delete_range(b"k5", b"k7").unwrap();
+        writer.delete_range(b"k2", b"k4").unwrap();
+        writer.finish().unwrap()
+    };
+    assert_eq!(
+        info,
+        ExternalSstFileInfo {
+            file_path: writer_path.clone(),
+            smallest_key: Some(b"k1".to_vec()),
+            largest_key: Some(b"k4".to_vec()),
+            sequence_number: 0,
+            file_size: std::fs::metadata(&writer_path).unwrap().len(),
+            num_entries: 3,
+            num_range_del_entries: 2,
+        }
+    );
+
+    let db = DB::open_default(&db_path).unwrap();
+    for key in [b"k0", b"k3", b"k5", b"k6", b"k7"] {
+        db.put(key, b"old").unwrap();
+    }
+    db.ingest_external_file(vec![&writer_path]).unwrap();
+    assert_eq!(db.get(b"k0").unwrap().unwrap(), b"old");
+    assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+    assert!(db.get(b"k3").unwrap().is_none());
+    assert!(db.get(b"k5").unwrap().is_none());
+    assert!(db.get(b"k6").unwrap().is_none());
+    assert_eq!(db.get(b"k7").unwrap().unwrap(), b"old");
+}
+
+#[test]
+fn sst_file_writer_fail_if_not_bottommost_level() {
+    let db_path = DBPath::new("_rust_rocksdb_sstfilewritertest_bottommost");
+    let dir = tempfile::Builder::new()
+        .prefix("_rust_rocksdb_sstfilewritertest_bottommost")
+        .tempdir()
+        .expect("Failed to create temporary path for file writer.");
+
+    let opts = Options::default();
+    let write_sst = |name: &str, key: &[u8]| {
+        let path = dir.path().join(name);
+        let mut writer = SstFileWriter::create(&opts);
+        writer.open(&path).unwrap();
+        writer.put(key, b"v").unwrap();
+        writer.finish().unwrap();
+        path
+    };
+
+    let db = DB::open_default(&db_path).unwrap();
+    db.put(b"k2", b"old").unwrap();
+    db.flush().unwrap();
+
+    let mut ingest_opts = IngestExternalFileOptions::default();
+    ingest_opts.set_fail_if_not_bottommost_level(true);
+    // Overlaps the flushed file, so it cannot go to the last level.
+    let err = db
+        .ingest_external_file_opts(&ingest_opts, vec![write_sst("overlapping", b"k2")])
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TryAgain);
+    assert_eq!(db.get(b"k2").unwrap().unwrap(), b"old");
+
+    db.ingest_external_file_opts(&ingest_opts, vec![write_sst("disjoint", b"k9")])
+        .unwrap();
+    assert_eq!(db.get(b"k9").unwrap().unwrap(), b"v");
+}
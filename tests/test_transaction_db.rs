@@ -18,8 +18,8 @@ mod util;
 use pretty_assertions::assert_eq;
 
 use rocksdb::{
-    CuckooTableOptions, DBAccess, Direction, Error, ErrorKind, IteratorMode, Options, ReadOptions,
-    SliceTransform, TransactionDB, TransactionDBOptions, TransactionOptions,
+    CuckooTableOptions, DBAccess, Direction, Error, ErrorKind, ErrorSubCode, IteratorMode, Options,
+    ReadOptions, SliceTransform, TransactionDB, TransactionDBOptions, TransactionOptions,
     WriteBatchWithTransaction, WriteOptions, DB,
 };
 use util::DBPath;
@@ -435,6 +435,8 @@ fn transaction() {
         let txn2 = db.transaction();
         let err = txn2.put(b"k1", b"v3").unwrap_err();
         assert_eq!(err.kind(), ErrorKind::TimedOut);
+        assert_eq!(err.subcode(), ErrorSubCode::LockTimeout);
+        assert!(err.is_retryable());
 
         // modify same key directly, should also get TimedOut
         let err = db.put(b"k1", b"v4").unwrap_err();
@@ -574,6 +576,44 @@ fn transaction_cf() {
     }
 }
 
+#[test]
+fn single_delete() {
+    let path = DBPath::new("_rust_rocksdb_transaction_db_single_delete");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db: TransactionDB =
+            TransactionDB::open_cf(&opts, &TransactionDBOptions::default(), &path, ["cf1"])
+                .unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+
+        db.put(b"k1", b"v1").unwrap();
+        db.put_cf(&cf1, b"k2", b"v2").unwrap();
+        db.single_delete(b"k1").unwrap();
+        db.single_delete_cf(&cf1, b"k2").unwrap();
+        assert!(db.get(b"k1").unwrap().is_none());
+        assert!(db.get_cf(&cf1, b"k2").unwrap().is_none());
+
+        db.put(b"k3", b"v3").unwrap();
+        db.put_cf(&cf1, b"k4", b"v4").unwrap();
+        let txn = db.transaction();
+        txn.single_delete(b"k3").unwrap();
+        txn.single_delete_cf(&cf1, b"k4").unwrap();
+        assert!(txn.get(b"k3").unwrap().is_none());
+        assert!(txn.get_cf(&cf1, b"k4").unwrap().is_none());
+        assert_eq!(db.get(b"k3").unwrap().unwrap(), b"v3");
+
+        // the keys are locked by the transaction
+        let err = db.single_delete(b"k3").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+
+        txn.commit().unwrap();
+        assert!(db.get(b"k3").unwrap().is_none());
+        assert!(db.get_cf(&cf1, b"k4").unwrap().is_none());
+    }
+}
+
 #[test]
 fn transaction_snapshot() {
     let path = DBPath::new("_rust_rocksdb_transaction_db_transaction_snapshot");
@@ -693,3 +733,32 @@ fn test_snapshot_outlive_txn() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/fail/snapshot_outlive_transaction.rs");
 }
+
+#[test]
+fn close() {
+    let path = DBPath::new("_rust_rocksdb_transaction_db_close");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let txn_db_opts = TransactionDBOptions::default();
+    {
+        let mut write_opts = WriteOptions::default();
+        write_opts.disable_wal(true);
+        let db: TransactionDB =
+            TransactionDB::open_cf(&opts, &txn_db_opts, &path, ["cf1"]).unwrap();
+        db.put_cf_opt(&db.cf_handle("cf1").unwrap(), b"k1", b"v1", &write_opts)
+            .unwrap();
+        db.close().unwrap();
+    }
+    {
+        let db: TransactionDB =
+            TransactionDB::open_cf(&opts, &txn_db_opts, &path, ["cf1"]).unwrap();
+        assert_eq!(
+            db.get_cf(&db.cf_handle("cf1").unwrap(), b"k1")
+                .unwrap()
+                .unwrap(),
+            b"v1"
+        );
+        db.close().unwrap();
+    }
+}